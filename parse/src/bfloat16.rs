@@ -0,0 +1,170 @@
+//! Parses hexadecimal float literals into bfloat16 values.
+
+use crate::{parse, parse_special, round_ieee, special_ieee, ParseHexfError, RoundingMode};
+
+// rounds `mantissa * 2^exponent` to bfloat16 bits,
+// and also returns an error describing the loss if the result is not exact.
+const fn round_hexbf16(
+    negative: bool,
+    mantissa: u128,
+    exponent: isize,
+    rounding: RoundingMode,
+) -> (u16, Option<ParseHexfError>) {
+    let (bits, loss) = round_ieee(negative, mantissa, exponent, rounding, 8, 7);
+    (bits as u16, loss)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of bfloat16.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// bfloat16 has the same exponent range as `f32` but only 8 significant bits,
+/// i.e. it is the upper half of `f32` bits.
+/// As with `parse_hexf32`, the literal should be exactly representable,
+/// and infinities and NaNs (optionally with a payload) are also accepted.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexbf16("0x1.fep127", false), Ok(0x7f7f));
+/// assert_eq!(parse_hexbf16("-0x1p-133", false), Ok(0x8001));
+/// assert!(parse_hexbf16("0x1.ffp0", false).is_err());
+/// ```
+pub fn parse_hexbf16(s: &str, allow_underscore: bool) -> Result<u16, ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 7)? {
+        return Ok(special_ieee(negative, special, 8, 7) as u16);
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    match round_hexbf16(negative, mantissa, exponent, RoundingMode::TowardZero) {
+        (bits, None) => Ok(bits),
+        (_, Some(err)) => Err(err.locate(s.as_bytes())),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of bfloat16,
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexbf16` and never rounded.
+pub fn parse_hexbf16_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<u16, ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 7)? {
+        return Ok(special_ieee(negative, special, 8, 7) as u16);
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    Ok(round_hexbf16(negative, mantissa, exponent, rounding).0)
+}
+
+/// Tries to parse a hexadecimal float literal to `half::bf16`.
+/// This is same to `parse_hexbf16` but returns the value instead of the bits,
+/// and requires the `half` feature.
+#[cfg(feature = "half")]
+pub fn parse_hexbf16_half(s: &str, allow_underscore: bool) -> Result<half::bf16, ParseHexfError> {
+    parse_hexbf16(s, allow_underscore).map(half::bf16::from_bits)
+}
+
+#[test]
+fn test_parse_hexbf16() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexbf16(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexbf16("0x0p0", false), Ok(0x0000));
+    assert_eq!(parse_hexbf16("-0x0p0", false), Ok(0x8000));
+    assert_eq!(parse_hexbf16("0x1p0", false), Ok(0x3f80));
+    assert_eq!(parse_hexbf16("-0x1.8p1", false), Ok(0xc040));
+    assert_eq!(parse_hexbf16("0x1.98p-4", false), Ok(0x3dcc));
+    assert_eq!(parse_hexbf16("0x1.fep127", false), Ok(0x7f7f));
+    assert_eq!(parse_hexbf16("0x1p-126", false), Ok(0x0080));
+    assert_eq!(parse_hexbf16("0x0.fep-126", false), Ok(0x007f));
+    assert_eq!(parse_hexbf16("0x1p-133", false), Ok(0x0001));
+    assert_eq!(parse_hexbf16("inf", false), Ok(0x7f80));
+    assert_eq!(parse_hexbf16("-inf", false), Ok(0xff80));
+    assert_eq!(parse_hexbf16("nan", false), Ok(0x7fc0));
+    assert_eq!(parse_hexbf16("-snan:0x1", false), Ok(0xff81));
+
+    assert_eq!(kind("0x1p128"), Overflow);
+    assert_eq!(kind("0x1p-134"), Underflow);
+    assert_eq!(kind("0x1.01p0"), Inexact);
+    assert_eq!(kind("0x1.99999ap-4"), Inexact);
+    assert_eq!(kind("nan:0x80"), NanPayloadOverflow);
+    assert_eq!(kind("snan:0x40"), NanPayloadOverflow);
+
+    let err = parse_hexbf16("0x1.01p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(5));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 8 significant bits fit, bits from the digit at byte 5 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexbf16_rounded() {
+    use crate::RoundingMode::*;
+
+    assert_eq!(
+        parse_hexbf16_rounded("0x1.99999ap-4", false, NearestTiesEven),
+        Ok(0x3dcd)
+    );
+    assert_eq!(
+        parse_hexbf16_rounded("0x1.99999ap-4", false, TowardZero),
+        Ok(0x3dcc)
+    );
+    assert_eq!(
+        parse_hexbf16_rounded("0x1.01p0", false, NearestTiesEven),
+        Ok(0x3f80)
+    );
+    assert_eq!(
+        parse_hexbf16_rounded("0x1.03p0", false, NearestTiesEven),
+        Ok(0x3f82)
+    );
+    assert_eq!(
+        parse_hexbf16_rounded("0x1.ffp127", false, NearestTiesEven),
+        Ok(0x7f80)
+    );
+    assert_eq!(
+        parse_hexbf16_rounded("-0x1p128", false, TowardZero),
+        Ok(0xff7f)
+    );
+}
+
+#[test]
+fn test_round_hexbf16_f32() {
+    // bfloat16 is the upper half of f32, so any f32 with zero lower bits should be exact,
+    // and truncating other f32 values should only clear lower bits
+    for upper in 0..0x7f80u32 {
+        for &lower in &[0, 1, 0x8000, 0xffff] {
+            let v = f32::from_bits(upper << 16 | lower);
+            let (mantissa, exponent) = if v == 0.0 {
+                (0, 0)
+            } else {
+                let biased = (v.to_bits() >> 23) as isize;
+                let fraction = (v.to_bits() & 0x7f_ffff) as u128;
+                if biased == 0 {
+                    (fraction, -149)
+                } else {
+                    (fraction | 0x80_0000, biased - 150)
+                }
+            };
+            let (bits, loss) = round_hexbf16(false, mantissa, exponent, RoundingMode::TowardZero);
+            assert_eq!(bits as u32, upper);
+            assert_eq!(loss.is_none(), lower == 0);
+        }
+    }
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_parse_hexbf16_half() {
+    assert_eq!(
+        parse_hexbf16_half("0x1.8p1", false),
+        Ok(half::bf16::from_f32(3.0))
+    );
+    assert_eq!(
+        parse_hexbf16_half("-0x1.fep127", false),
+        Ok(half::bf16::MIN)
+    );
+    assert!(parse_hexbf16_half("0x1p128", false).is_err());
+}
@@ -0,0 +1,183 @@
+//! Parses hexadecimal float literals into IEEE 754 binary128 (quadruple precision) values.
+
+use crate::{parse, parse_special, round_ieee, special_ieee, ParseHexfError, RoundingMode};
+
+// rounds `mantissa * 2^exponent` to binary128 bits,
+// and also returns an error describing the loss if the result is not exact.
+const fn round_hexf128(
+    negative: bool,
+    mantissa: u128,
+    exponent: isize,
+    rounding: RoundingMode,
+) -> (u128, Option<ParseHexfError>) {
+    round_ieee(negative, mantissa, exponent, rounding, 15, 112)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IEEE 754 binary128 (`f128`).
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// As with `parse_hexf32`, the literal should be exactly representable,
+/// and infinities and NaNs (optionally with a payload) are also accepted.
+/// The result can be converted to the actual quadruple precision type with its `from_bits`;
+/// see also `parse_hexf128_native`.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf128("0x1p0", false), Ok(0x3fff << 112));
+/// assert_eq!(
+///     parse_hexf128("0x1.999999999999999999999999999ap-4", false),
+///     Ok(0x3ffb_9999_9999_9999_9999_9999_9999_999a)
+/// );
+/// assert!(parse_hexf128("0x1.00000000000000000000000000008p0", false).is_err());
+/// ```
+pub fn parse_hexf128(s: &str, allow_underscore: bool) -> Result<u128, ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 112)? {
+        return Ok(special_ieee(negative, special, 15, 112));
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    match round_hexf128(negative, mantissa, exponent, RoundingMode::TowardZero) {
+        (bits, None) => Ok(bits),
+        (_, Some(err)) => Err(err.locate(s.as_bytes())),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IEEE 754 binary128 (`f128`),
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexf128` and never rounded.
+pub fn parse_hexf128_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<u128, ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 112)? {
+        return Ok(special_ieee(negative, special, 15, 112));
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    Ok(round_hexf128(negative, mantissa, exponent, rounding).0)
+}
+
+/// Tries to parse a hexadecimal float literal to the primitive `f128` type.
+/// This is same to `parse_hexf128` but returns the value instead of the bits,
+/// and requires the `f128` feature and a nightly compiler.
+#[cfg(feature = "f128")]
+pub fn parse_hexf128_native(s: &str, allow_underscore: bool) -> Result<f128, ParseHexfError> {
+    parse_hexf128(s, allow_underscore).map(f128::from_bits)
+}
+
+#[test]
+fn test_parse_hexf128() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf128(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf128("0x0p0", false), Ok(0));
+    assert_eq!(parse_hexf128("-0x0p0", false), Ok(1 << 127));
+    assert_eq!(parse_hexf128("0x1p0", false), Ok(0x3fff << 112));
+    assert_eq!(parse_hexf128("-0x1.8p1", false), Ok(0xc000_8000 << 96));
+    assert_eq!(
+        parse_hexf128("0x1.999999999999ap-4", false),
+        Ok(0x3ffb_9999_9999_9999_a000_0000_0000_0000)
+    );
+    assert_eq!(
+        parse_hexf128("0x1.ffff_ffff_ffff_ffff_ffff_ffff_ffffp16383", true),
+        Ok(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff)
+    );
+    assert_eq!(parse_hexf128("0x1p-16382", false), Ok(1 << 112));
+    assert_eq!(parse_hexf128("0x1p-16494", false), Ok(1));
+    assert_eq!(
+        parse_hexf128("0x1.0000000000000000000000000001p0", false),
+        Ok(0x3fff_0000_0000_0000_0000_0000_0000_0001)
+    );
+    assert_eq!(
+        parse_hexf128("0x8000000000000000000000000000.8p-111", false),
+        Ok(0x3fff_0000_0000_0000_0000_0000_0000_0001)
+    );
+    assert_eq!(parse_hexf128("inf", false), Ok(0x7fff << 112));
+    assert_eq!(parse_hexf128("-inf", false), Ok(0xffff << 112));
+    assert_eq!(parse_hexf128("nan", false), Ok(0x7fff_8000 << 96));
+    assert_eq!(
+        parse_hexf128("-snan:0x1", false),
+        Ok(0xffff_0000_0000_0000_0000_0000_0000_0001)
+    );
+    assert_eq!(
+        parse_hexf128("nan:0xffff_ffff_ffff_ffff_ffff_ffff_ffff", true),
+        Ok(u128::MAX >> 1)
+    );
+
+    assert_eq!(kind("0x1p16384"), Overflow);
+    assert_eq!(kind("0x1p-16495"), Underflow);
+    assert_eq!(kind("0x1.00000000000000000000000000008p0"), Inexact);
+    assert_eq!(kind("0x1.8p-16494"), Inexact);
+    assert_eq!(
+        kind("nan:0x1_0000_0000_0000_0000_0000_0000_0000"),
+        NanPayloadOverflow
+    );
+
+    let err = parse_hexf128("0x1.00000000000000000000000000008p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(32));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 113 significant bits fit, bits from the digit at byte 32 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexf128_rounded() {
+    use crate::RoundingMode::*;
+
+    let one = 0x3fff << 112;
+    assert_eq!(
+        parse_hexf128_rounded(
+            "0x1.00000000000000000000000000008p0",
+            false,
+            NearestTiesEven
+        ),
+        Ok(one)
+    );
+    assert_eq!(
+        parse_hexf128_rounded(
+            "0x1.00000000000000000000000000018p0",
+            false,
+            NearestTiesEven
+        ),
+        Ok(one + 2)
+    );
+    assert_eq!(
+        parse_hexf128_rounded(
+            "0x1.000000000000000000000000000000000000001p0",
+            false,
+            TowardPositive
+        ),
+        Ok(one + 1)
+    );
+    assert_eq!(
+        parse_hexf128_rounded(
+            "0x1.999999999999999999999999999999999p-4",
+            false,
+            NearestTiesEven
+        ),
+        Ok(0x3ffb_9999_9999_9999_9999_9999_9999_999a)
+    );
+    assert_eq!(
+        parse_hexf128_rounded("-0x1p16384", false, TowardZero),
+        Ok(0xfffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff)
+    );
+    assert_eq!(
+        parse_hexf128_rounded("0x1p-20000", false, TowardPositive),
+        Ok(1)
+    );
+}
+
+#[cfg(feature = "f128")]
+#[test]
+fn test_parse_hexf128_native() {
+    assert_eq!(parse_hexf128_native("0x1.8p1", false), Ok(3.0f128));
+    assert_eq!(
+        parse_hexf128_native("-0x1.ffff_ffff_ffff_ffff_ffff_ffff_ffffp16383", true),
+        Ok(f128::MIN)
+    );
+    assert!(parse_hexf128_native("0x1p16384", false).is_err());
+}
@@ -0,0 +1,199 @@
+//! Parses hexadecimal float literals into IEEE 754 binary16 (half precision) values.
+
+use crate::{parse, parse_special, round_ieee, special_ieee, ParseHexfError, RoundingMode};
+
+// rounds `mantissa * 2^exponent` to binary16 bits,
+// and also returns an error describing the loss if the result is not exact.
+const fn round_hexf16(
+    negative: bool,
+    mantissa: u128,
+    exponent: isize,
+    rounding: RoundingMode,
+) -> (u16, Option<ParseHexfError>) {
+    let (bits, loss) = round_ieee(negative, mantissa, exponent, rounding, 5, 10);
+    (bits as u16, loss)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IEEE 754 binary16 (`f16`).
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// As with `parse_hexf32`, the literal should be exactly representable,
+/// and infinities and NaNs (optionally with a payload) are also accepted.
+/// The result can be converted to the actual half precision type with its `from_bits`;
+/// see also `parse_hexf16_half` and `parse_hexf16_native`.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf16("0x1.ffcp15", false), Ok(0x7bff));
+/// assert_eq!(parse_hexf16("-0x1p-24", false), Ok(0x8001));
+/// assert!(parse_hexf16("0x1.ffep15", false).is_err());
+/// ```
+pub fn parse_hexf16(s: &str, allow_underscore: bool) -> Result<u16, ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 10)? {
+        return Ok(special_ieee(negative, special, 5, 10) as u16);
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    match round_hexf16(negative, mantissa, exponent, RoundingMode::TowardZero) {
+        (bits, None) => Ok(bits),
+        (_, Some(err)) => Err(err.locate(s.as_bytes())),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IEEE 754 binary16 (`f16`),
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexf16` and never rounded.
+pub fn parse_hexf16_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<u16, ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 10)? {
+        return Ok(special_ieee(negative, special, 5, 10) as u16);
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    Ok(round_hexf16(negative, mantissa, exponent, rounding).0)
+}
+
+/// Tries to parse a hexadecimal float literal to `half::f16`.
+/// This is same to `parse_hexf16` but returns the value instead of the bits,
+/// and requires the `half` feature.
+#[cfg(feature = "half")]
+pub fn parse_hexf16_half(s: &str, allow_underscore: bool) -> Result<half::f16, ParseHexfError> {
+    parse_hexf16(s, allow_underscore).map(half::f16::from_bits)
+}
+
+/// Tries to parse a hexadecimal float literal to the primitive `f16` type.
+/// This is same to `parse_hexf16` but returns the value instead of the bits,
+/// and requires the `f16` feature and a nightly compiler.
+#[cfg(feature = "f16")]
+pub fn parse_hexf16_native(s: &str, allow_underscore: bool) -> Result<f16, ParseHexfError> {
+    parse_hexf16(s, allow_underscore).map(f16::from_bits)
+}
+
+#[test]
+fn test_parse_hexf16() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf16(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf16("0x0p0", false), Ok(0x0000));
+    assert_eq!(parse_hexf16("-0x0p0", false), Ok(0x8000));
+    assert_eq!(parse_hexf16("0x1p0", false), Ok(0x3c00));
+    assert_eq!(parse_hexf16("-0x1.8p1", false), Ok(0xc200));
+    assert_eq!(parse_hexf16("0x1.554p-2", false), Ok(0x3555));
+    assert_eq!(parse_hexf16("0x1.ffcp15", false), Ok(0x7bff));
+    assert_eq!(parse_hexf16("0x1p-14", false), Ok(0x0400));
+    assert_eq!(parse_hexf16("0x0.ffcp-14", false), Ok(0x03ff));
+    assert_eq!(parse_hexf16("0x1p-24", false), Ok(0x0001));
+    assert_eq!(parse_hexf16("0x1_0p-4", true), Ok(0x3c00));
+    assert_eq!(parse_hexf16("inf", false), Ok(0x7c00));
+    assert_eq!(parse_hexf16("-inf", false), Ok(0xfc00));
+    assert_eq!(parse_hexf16("nan", false), Ok(0x7e00));
+    assert_eq!(parse_hexf16("nan:0x1ff", false), Ok(0x7fff));
+    assert_eq!(parse_hexf16("-snan:0x1", false), Ok(0xfc01));
+
+    assert_eq!(kind("0x1p16"), Overflow);
+    assert_eq!(kind("0x1.ffep15"), Inexact);
+    assert_eq!(kind("0x1p-25"), Underflow);
+    assert_eq!(kind("0x1.002p0"), Inexact);
+    assert_eq!(kind("0x1.8p-24"), Inexact);
+    assert_eq!(kind("nan:0x400"), NanPayloadOverflow);
+    assert_eq!(kind("snan:0x200"), NanPayloadOverflow);
+    assert_eq!(kind("0x1.0"), MissingExponent);
+
+    let err = parse_hexf16("0x1.002p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(6));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 11 significant bits fit, bits from the digit at byte 6 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexf16_rounded() {
+    use crate::RoundingMode::*;
+
+    assert_eq!(
+        parse_hexf16_rounded("0x1.002p0", false, NearestTiesEven),
+        Ok(0x3c00)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("0x1.006p0", false, NearestTiesEven),
+        Ok(0x3c02)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("0x1.002p0", false, TowardPositive),
+        Ok(0x3c01)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("0x1.999999p-4", false, NearestTiesEven),
+        Ok(0x2e66)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("0x1.ffep15", false, NearestTiesEven),
+        Ok(0x7c00)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("-0x1p16", false, TowardZero),
+        Ok(0xfbff)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("0x1p-25", false, TowardPositive),
+        Ok(0x0001)
+    );
+    assert_eq!(
+        parse_hexf16_rounded("0x0.ffep-14", false, NearestTiesEven),
+        Ok(0x0400)
+    );
+}
+
+#[test]
+fn test_round_hexf16_exact() {
+    // every finite value should be read back exactly,
+    // and any extra bits below the least significant bit should be truncated
+    for bits in 0..0x7c00u16 {
+        let biased = (bits >> 10) as isize;
+        let fraction = (bits & 0x3ff) as u128;
+        let (mantissa, exponent) = if biased == 0 {
+            (fraction, -24)
+        } else {
+            (fraction | 0x400, biased - 25)
+        };
+        assert_eq!(
+            round_hexf16(false, mantissa, exponent, RoundingMode::TowardZero),
+            (bits, None)
+        );
+        assert_eq!(
+            round_hexf16(
+                true,
+                mantissa << 3 | 1,
+                exponent - 3,
+                RoundingMode::TowardZero
+            )
+            .0,
+            bits | 0x8000
+        );
+    }
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_parse_hexf16_half() {
+    assert_eq!(
+        parse_hexf16_half("0x1.8p1", false),
+        Ok(half::f16::from_f32(3.0))
+    );
+    assert_eq!(parse_hexf16_half("-0x1.ffcp15", false), Ok(half::f16::MIN));
+    assert!(parse_hexf16_half("0x1p16", false).is_err());
+}
+
+#[cfg(feature = "f16")]
+#[test]
+fn test_parse_hexf16_native() {
+    assert_eq!(parse_hexf16_native("0x1.8p1", false), Ok(3.0f16));
+    assert_eq!(parse_hexf16_native("-0x1.ffcp15", false), Ok(f16::MIN));
+    assert!(parse_hexf16_native("0x1p16", false).is_err());
+}
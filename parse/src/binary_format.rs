@@ -0,0 +1,375 @@
+//! Parses hexadecimal float literals into user-defined binary floating point formats.
+
+use crate::{
+    parse, parse_special, round_binary, special_ieee, ParseHexfError, ParseHexfErrorKind,
+    RoundingMode, Special, UNSUPPORTED_SPECIAL,
+};
+
+const UNSUPPORTED_FORMAT: ParseHexfError = ParseHexfError {
+    kind: ParseHexfErrorKind::UnsupportedFormat,
+    ..UNSUPPORTED_SPECIAL
+};
+
+/// A description of a binary floating point format,
+/// which consists of the sign bit, the biased exponent field and the fraction field
+/// from the most significant bit.
+///
+/// As in IEEE 754, the zero biased exponent denotes subnormal numbers (and zeroes)
+/// and other exponents have an implicit leading bit in the significand.
+/// How the maximal biased exponent is used depends on `has_infinity` and `has_nan`:
+///
+/// * If `has_infinity` is true, the maximal biased exponent is reserved as in IEEE 754,
+///   i.e. it is an infinity with the zero fraction and a NaN otherwise (if `has_nan` is true).
+///
+/// * If `has_infinity` is false but `has_nan` is true, the maximal biased exponent is used
+///   for finite numbers except for the all-ones bit pattern, which is a single NaN for each sign
+///   (as in OCP FP8 E4M3).
+///
+/// * If both are false, every bit pattern is a finite number (as in OCP FP6 and FP4).
+///
+/// The format should have 1 to 29 exponent bits, at least 1 fraction bit,
+/// and at most 128 bits in total including the sign bit.
+/// The bias should be also less than 2^29 in its magnitude.
+/// Other formats result in `ParseHexfErrorKind::UnsupportedFormat` when parsed into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BinaryFormat {
+    /// The number of bits in the biased exponent field.
+    pub exponent_bits: u32,
+    /// The number of bits in the fraction field, excluding the implicit leading bit.
+    pub fraction_bits: u32,
+    /// True if the format has infinities.
+    pub has_infinity: bool,
+    /// True if the format has NaNs.
+    pub has_nan: bool,
+    /// The exponent bias, so that the biased exponent 1 has the actual exponent `1 - bias`.
+    pub bias: i32,
+}
+
+impl BinaryFormat {
+    /// IEEE 754 binary16 (half precision).
+    pub const BINARY16: BinaryFormat = BinaryFormat::ieee(5, 10);
+    /// IEEE 754 binary32 (single precision, `f32`).
+    pub const BINARY32: BinaryFormat = BinaryFormat::ieee(8, 23);
+    /// IEEE 754 binary64 (double precision, `f64`).
+    pub const BINARY64: BinaryFormat = BinaryFormat::ieee(11, 52);
+    /// IEEE 754 binary128 (quadruple precision).
+    pub const BINARY128: BinaryFormat = BinaryFormat::ieee(15, 112);
+    /// bfloat16, which has the same exponent range as `f32`.
+    pub const BFLOAT16: BinaryFormat = BinaryFormat::ieee(8, 7);
+    /// NVIDIA TensorFloat-32, which has the same exponent range as `f32`
+    /// and the same precision as binary16.
+    pub const TF32: BinaryFormat = BinaryFormat::ieee(8, 10);
+    /// OCP FP8 E4M3, which has no infinities and a single NaN per sign,
+    /// so that the largest finite value is extended to 448.
+    pub const FP8_E4M3: BinaryFormat = BinaryFormat {
+        exponent_bits: 4,
+        fraction_bits: 3,
+        has_infinity: false,
+        has_nan: true,
+        bias: 7,
+    };
+    /// OCP FP8 E5M2, which is same to an IEEE 754-like format with the same field widths.
+    pub const FP8_E5M2: BinaryFormat = BinaryFormat::ieee(5, 2);
+
+    /// Returns an IEEE 754-like format with given field widths,
+    /// which has infinities and NaNs and the bias of `2^(exponent_bits - 1) - 1`.
+    pub const fn ieee(exponent_bits: u32, fraction_bits: u32) -> BinaryFormat {
+        BinaryFormat {
+            exponent_bits,
+            fraction_bits,
+            has_infinity: true,
+            has_nan: true,
+            bias: (1 << (exponent_bits - 1)) - 1,
+        }
+    }
+
+    /// Returns the total number of bits including the sign bit.
+    pub const fn total_bits(&self) -> u32 {
+        1 + self.exponent_bits + self.fraction_bits
+    }
+
+    // fails if the format cannot be handled, see the type documentation.
+    // the limits keep every exponent in `round_binary` (including its guard range)
+    // well within i32, so that it cannot overflow even with the 32-bit `isize`.
+    fn validate(&self) -> Result<(), ParseHexfError> {
+        if self.exponent_bits < 1
+            || self.exponent_bits > 29
+            || self.fraction_bits < 1
+            || self.fraction_bits > 127 - self.exponent_bits
+            || self.bias <= -(1 << 29)
+            || self.bias >= (1 << 29)
+        {
+            return Err(UNSUPPORTED_FORMAT);
+        }
+        Ok(())
+    }
+}
+
+// returns the bits of the special value with given sign in given binary format.
+fn special_binary(
+    negative: bool,
+    special: Special,
+    format: &BinaryFormat,
+) -> Result<u128, ParseHexfError> {
+    let exponent_bits = format.exponent_bits;
+    let fraction_bits = format.fraction_bits;
+    match special {
+        Special::Infinity if !format.has_infinity => Err(UNSUPPORTED_SPECIAL),
+        Special::Nan(_) if !format.has_nan => Err(UNSUPPORTED_SPECIAL),
+        // the single NaN only has the default payload
+        Special::Nan(fraction) if !format.has_infinity => {
+            if fraction != 1 << (fraction_bits - 1) {
+                return Err(ParseHexfError {
+                    kind: ParseHexfErrorKind::NanPayloadOverflow,
+                    ..UNSUPPORTED_SPECIAL
+                });
+            }
+            let sign = (negative as u128) << (exponent_bits + fraction_bits);
+            Ok(sign | ((1 << (exponent_bits + fraction_bits)) - 1))
+        }
+        _ => Ok(special_ieee(
+            negative,
+            special,
+            exponent_bits,
+            fraction_bits,
+        )),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of given binary format.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// The result has the sign bit at `format.total_bits() - 1`, and any higher bits are zeroes.
+/// As with `parse_hexf32`, the literal should be exactly representable in the format.
+/// Infinities and NaNs are accepted only if the format has them,
+/// and otherwise result in `ParseHexfErrorKind::UnsupportedSpecial`.
+/// If the format has a single NaN per sign, `nan` is the only accepted NaN.
+///
+/// Formats which don't meet the requirements of `BinaryFormat`
+/// result in `ParseHexfErrorKind::UnsupportedFormat`.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf_as("0x1.8p1", false, &BinaryFormat::TF32), Ok(0x2_0200));
+///
+/// // OCP FP6 E2M3, which has neither infinities nor NaNs
+/// let e2m3 = BinaryFormat {
+///     exponent_bits: 2,
+///     fraction_bits: 3,
+///     has_infinity: false,
+///     has_nan: false,
+///     bias: 1,
+/// };
+/// assert_eq!(parse_hexf_as("-0x1.ep2", false, &e2m3), Ok(0x3f));
+/// assert!(parse_hexf_as("0x1p3", false, &e2m3).is_err());
+/// assert!(parse_hexf_as("inf", false, &e2m3).is_err());
+/// ```
+pub fn parse_hexf_as(
+    s: &str,
+    allow_underscore: bool,
+    format: &BinaryFormat,
+) -> Result<u128, ParseHexfError> {
+    format.validate()?;
+    let bytes = s.as_bytes();
+    if let Some((negative, special)) = parse_special(bytes, allow_underscore, format.fraction_bits)?
+    {
+        return special_binary(negative, special, format);
+    }
+    let (negative, mantissa, exponent) = parse(bytes, allow_underscore)?;
+    match round_binary(
+        negative,
+        mantissa,
+        exponent,
+        RoundingMode::TowardZero,
+        format,
+    ) {
+        (bits, None) => Ok(bits),
+        (_, Some(err)) => Err(err.locate(bytes)),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of given binary format,
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Formats without infinities always overflow to the largest finite value (i.e. saturate).
+/// Infinities and NaNs are accepted as in `parse_hexf_as` and never rounded.
+pub fn parse_hexf_as_rounded(
+    s: &str,
+    allow_underscore: bool,
+    format: &BinaryFormat,
+    rounding: RoundingMode,
+) -> Result<u128, ParseHexfError> {
+    format.validate()?;
+    let bytes = s.as_bytes();
+    if let Some((negative, special)) = parse_special(bytes, allow_underscore, format.fraction_bits)?
+    {
+        return special_binary(negative, special, format);
+    }
+    let (negative, mantissa, exponent) = parse(bytes, allow_underscore)?;
+    Ok(round_binary(negative, mantissa, exponent, rounding, format).0)
+}
+
+#[cfg(test)]
+const E2M3: BinaryFormat = BinaryFormat {
+    exponent_bits: 2,
+    fraction_bits: 3,
+    has_infinity: false,
+    has_nan: false,
+    bias: 1,
+};
+
+#[cfg(test)]
+const E4M3: BinaryFormat = BinaryFormat::FP8_E4M3;
+
+#[test]
+fn test_parse_hexf_as() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s, format| parse_hexf_as(s, true, format).unwrap_err().kind();
+
+    // IEEE 754-like formats
+    let binary32 = |s| parse_hexf_as(s, true, &BinaryFormat::BINARY32);
+    assert_eq!(binary32("0x1.99999ap-4"), Ok(0.1f32.to_bits() as u128));
+    assert_eq!(binary32("-0x1p-149"), Ok(0x8000_0001));
+    assert_eq!(binary32("-inf"), Ok(0xff80_0000));
+    assert_eq!(binary32("snan:0x1"), Ok(0x7f80_0001));
+    assert_eq!(
+        parse_hexf_as("0x1.fffffffffffffp1023", false, &BinaryFormat::BINARY64),
+        Ok(f64::MAX.to_bits() as u128)
+    );
+    assert_eq!(
+        parse_hexf_as("-0x1p-16494", false, &BinaryFormat::BINARY128),
+        Ok(1 << 127 | 1)
+    );
+    assert_eq!(kind("0x1p128", &BinaryFormat::BINARY32), Overflow);
+    assert_eq!(kind("0x1.000002p0", &BinaryFormat::BFLOAT16), Inexact);
+
+    // without infinities and NaNs
+    assert_eq!(parse_hexf_as("0x0p0", false, &E2M3), Ok(0x00));
+    assert_eq!(parse_hexf_as("-0x0p0", false, &E2M3), Ok(0x20));
+    assert_eq!(parse_hexf_as("0x1p0", false, &E2M3), Ok(0x08));
+    assert_eq!(parse_hexf_as("0x1p-3", false, &E2M3), Ok(0x01));
+    assert_eq!(parse_hexf_as("0x1.ep2", false, &E2M3), Ok(0x1f));
+    assert_eq!(kind("0x1p3", &E2M3), Overflow);
+    assert_eq!(kind("0x1p-4", &E2M3), Underflow);
+    assert_eq!(kind("0x1.1p0", &E2M3), Inexact);
+    assert_eq!(kind("inf", &E2M3), UnsupportedSpecial);
+    assert_eq!(kind("nan", &E2M3), UnsupportedSpecial);
+
+    // without infinities but with a single NaN
+    assert_eq!(parse_hexf_as("0x1.cp8", false, &E4M3), Ok(0x7e));
+    assert_eq!(parse_hexf_as("-nan", false, &E4M3), Ok(0xff));
+    assert_eq!(kind("0x1.ep8", &E4M3), Overflow);
+    assert_eq!(kind("-inf", &E4M3), UnsupportedSpecial);
+    assert_eq!(kind("nan:0x1", &E4M3), NanPayloadOverflow);
+    assert_eq!(kind("snan:0x1", &E4M3), NanPayloadOverflow);
+
+    // with a custom bias
+    let biased = BinaryFormat {
+        bias: -10,
+        ..BinaryFormat::BINARY16
+    };
+    assert_eq!(parse_hexf_as("0x1p11", false, &biased), Ok(0x0400));
+    assert_eq!(parse_hexf_as("0x1p1", false, &biased), Ok(0x0001));
+    assert_eq!(kind("0x1p0", &biased), Underflow);
+    assert_eq!(kind("0x1p41", &biased), Overflow);
+}
+
+#[test]
+fn test_parse_hexf_as_rounded() {
+    use crate::RoundingMode::*;
+
+    // saturates without infinities
+    assert_eq!(
+        parse_hexf_as_rounded("0x1.ep8", false, &E4M3, NearestTiesEven),
+        Ok(0x7e)
+    );
+    assert_eq!(
+        parse_hexf_as_rounded("-0x1p100", false, &E4M3, TowardNegative),
+        Ok(0xfe)
+    );
+    assert_eq!(
+        parse_hexf_as_rounded("0x1.dp8", false, &E4M3, NearestTiesEven),
+        Ok(0x7e)
+    );
+    assert_eq!(
+        parse_hexf_as_rounded("0x1p3", false, &E2M3, NearestTiesEven),
+        Ok(0x1f)
+    );
+    assert_eq!(
+        parse_hexf_as_rounded("0x1.1p0", false, &E2M3, TowardPositive),
+        Ok(0x09)
+    );
+    assert_eq!(
+        parse_hexf_as_rounded("0x1p128", false, &BinaryFormat::TF32, NearestTiesEven),
+        Ok(0x3_fc00)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_hexf_as_same_to_f32() {
+    // should be consistent with the dedicated parser for every rounding mode
+    for state in crate::XorShift64(0x853c_49e6_748f_ea9bu64).take(10000) {
+        let s = format!("0x{:x}p{}", state >> (state % 40), (state >> 48) as i8);
+        for &rounding in &[
+            RoundingMode::NearestTiesEven,
+            RoundingMode::TowardNegative,
+            RoundingMode::ToOdd,
+        ] {
+            let bits = parse_hexf_as_rounded(&s, false, &BinaryFormat::BINARY32, rounding);
+            let expected = crate::parse_hexf32_rounded(&s, false, rounding);
+            assert_eq!(bits, expected.map(|v| v.to_bits() as u128), "{}", s);
+        }
+    }
+}
+
+#[test]
+fn test_parse_hexf_as_unsupported() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |format| {
+        let kind = parse_hexf_as("0x1p0", false, &format).unwrap_err().kind();
+        let rounded = parse_hexf_as_rounded("0x1p0", false, &format, RoundingMode::ToOdd);
+        assert_eq!(rounded.unwrap_err().kind(), kind);
+        kind
+    };
+    assert_eq!(kind(BinaryFormat::ieee(15, 113)), UnsupportedFormat);
+    assert_eq!(kind(BinaryFormat::ieee(30, 1)), UnsupportedFormat);
+    assert_eq!(kind(BinaryFormat::ieee(1, 0)), UnsupportedFormat);
+    let huge = BinaryFormat {
+        fraction_bits: u32::MAX,
+        ..BinaryFormat::BINARY32
+    };
+    assert_eq!(kind(huge), UnsupportedFormat);
+    let biased = BinaryFormat {
+        bias: -(1 << 29),
+        ..BinaryFormat::BINARY32
+    };
+    assert_eq!(kind(biased), UnsupportedFormat);
+
+    // the extreme formats within the limits
+    let widest = BinaryFormat {
+        bias: -(1 << 29) + 1,
+        ..BinaryFormat::ieee(29, 1)
+    };
+    assert_eq!(parse_hexf_as("0x1p536870911", false, &widest), Ok(1));
+    assert_eq!(
+        parse_hexf_as("0x1p1073741822", false, &widest)
+            .unwrap_err()
+            .kind(),
+        Overflow
+    );
+    assert_eq!(
+        parse_hexf_as_rounded("0x1p99999999999", false, &widest, RoundingMode::TowardZero),
+        Ok((1 << 30) - 3)
+    );
+    let deepest = BinaryFormat {
+        bias: (1 << 29) - 1,
+        ..BinaryFormat::ieee(29, 98)
+    };
+    assert_eq!(
+        parse_hexf_as("-0x1p-536871008", false, &deepest),
+        Ok(1 << 127 | 1)
+    );
+}
@@ -0,0 +1,428 @@
+//! Formats exact decimal expansions of hexadecimal float literals.
+
+use crate::{parse_hexf_parts, HexFloatParts, ParseHexfError, INEXACT, OVERFLOW, UNDERFLOW};
+use core::fmt;
+
+// the range of binary exponents supported by `ExactDecimal`, which is same to binary128.
+// the most significant bit should be at most `2^MAX_EXPONENT`
+// and the least significant bit should be at least `2^MIN_EXPONENT`.
+const MAX_EXPONENT: isize = 16383;
+const MIN_EXPONENT: isize = -16494;
+
+// the number of 32-bit limbs enough for `significand * 2^exponent` if `exponent >= 0`,
+// or `significand * 5^-exponent` otherwise, where `5^k < 2^(k * 2322 / 1000 + 1)`.
+const fn limbs_for(exponent: isize) -> usize {
+    let bits = if exponent >= 0 {
+        exponent as usize
+    } else {
+        exponent.unsigned_abs() * 2322 / 1000 + 1
+    };
+    (128 + bits).div_ceil(32)
+}
+
+// the number of 9-digit decimal chunks enough for any integer with `limbs` limbs.
+const fn chunks_for(limbs: usize) -> usize {
+    (limbs * 32 * 30103 / 100000 + 1).div_ceil(9)
+}
+
+// the buffer sizes are chosen from the exponent, so that most values don't need
+// the largest buffer (about 10 KB in total) which can easily overflow small stacks.
+// they are enough for every `f32`, every `f64` and every supported value respectively.
+const SMALL_LIMBS: usize = limbs_for(-149);
+const MEDIUM_LIMBS: usize = limbs_for(-1074);
+const LARGE_LIMBS: usize = limbs_for(MIN_EXPONENT);
+
+/// An exact decimal expansion of a binary floating point number.
+///
+/// Every binary floating point number has a finite decimal expansion,
+/// which is written in full with the `Display` implementation without any exponent, e.g.
+/// `0.1000000000000000055511151231257827021181583404541015625` for `0x1.999999999999ap-4`.
+/// The integral part is `0` for numbers less than 1, and the fractional part is omitted
+/// for integers. Negative numbers including the negative zero have a `-` sign.
+///
+/// This doesn't allocate, so it can be written to any `core::fmt::Write` even without `std`.
+/// Therefore the supported range is limited to numbers with at most 128 significant bits
+/// whose bits are within the range of IEEE 754 binary128,
+/// i.e. from `2^-16494` to the most significant bit at `2^16383`,
+/// which covers every `f32` and `f64` and also every binary128 value.
+/// Formatting instead uses a stack buffer sized from the exponent,
+/// which is less than 1 KB for values within the range of `f64`
+/// but can be up to about 10 KB for values near the ends of the binary128 range.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// use core::fmt::Write;
+/// let mut s = String::new();
+/// write!(s, "{}", ExactDecimal::from_f64(0.1).unwrap()).unwrap();
+/// assert_eq!(s, "0.1000000000000000055511151231257827021181583404541015625");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExactDecimal {
+    // normalized parts within the supported range
+    parts: HexFloatParts,
+}
+
+impl ExactDecimal {
+    // checks the range of parts and makes a new value.
+    fn new(parts: HexFloatParts) -> Result<ExactDecimal, ParseHexfError> {
+        // normalization only increases the exponent, so any failure here means overflow
+        let parts = match parts.normalized() {
+            Some(parts) => parts,
+            None => return Err(OVERFLOW),
+        };
+        if !parts.is_zero() {
+            let msb = match parts.msb_exponent() {
+                Some(msb) if msb <= MAX_EXPONENT => msb,
+                _ => return Err(OVERFLOW),
+            };
+            if msb < MIN_EXPONENT {
+                return Err(UNDERFLOW);
+            }
+            if parts.exponent < MIN_EXPONENT {
+                let precision = (msb - MIN_EXPONENT + 1) as u32;
+                return Err(ParseHexfError {
+                    lost: Some((precision, MIN_EXPONENT)),
+                    ..INEXACT
+                });
+            }
+        }
+        Ok(ExactDecimal { parts })
+    }
+
+    /// Returns the exact decimal expansion of given `f64`, or `None` for infinities and NaNs.
+    pub fn from_f64(v: f64) -> Option<ExactDecimal> {
+        if !v.is_finite() {
+            return None;
+        }
+        let bits = v.to_bits();
+        let biased = (bits >> 52 & 0x7ff) as isize;
+        let fraction = bits & ((1 << 52) - 1);
+        let (significand, exponent) = if biased == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 1 << 52, biased - 1075)
+        };
+        let parts = HexFloatParts {
+            negative: v.is_sign_negative(),
+            significand: significand as u128,
+            exponent,
+        };
+        ExactDecimal::new(parts).ok()
+    }
+
+    /// Returns the exact decimal expansion of given `f32`, or `None` for infinities and NaNs.
+    pub fn from_f32(v: f32) -> Option<ExactDecimal> {
+        // every finite `f32` is exactly representable as `f64`
+        ExactDecimal::from_f64(v as f64)
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to its exact decimal expansion.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// The literal is parsed as in `parse_hexf_parts`, and then should be in the range
+/// supported by `ExactDecimal`, which is the range of IEEE 754 binary128
+/// even though literals can be much larger or smaller than that.
+/// Literals with the most significant bit above `2^16383` or below `2^-16494` result in
+/// `ParseHexfErrorKind::Overflow` or `ParseHexfErrorKind::Underflow` respectively,
+/// and literals with any bit below `2^-16494` result in `ParseHexfErrorKind::Inexact`.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// let d = parse_hexf_decimal("-0x1.8p-3", false).unwrap();
+/// assert_eq!(format!("{}", d), "-0.1875");
+/// assert!(parse_hexf_decimal("0x1p16384", false).is_err());
+/// ```
+pub fn parse_hexf_decimal(s: &str, allow_underscore: bool) -> Result<ExactDecimal, ParseHexfError> {
+    let parts = parse_hexf_parts(s, allow_underscore)?;
+    ExactDecimal::new(parts).map_err(|err| err.locate(s.as_bytes()))
+}
+
+// a fixed-size big integer in little-endian 32-bit limbs.
+struct BigUint<const LIMBS: usize> {
+    limbs: [u32; LIMBS],
+    len: usize,
+}
+
+impl<const LIMBS: usize> BigUint<LIMBS> {
+    fn from_u128(mut v: u128) -> BigUint<LIMBS> {
+        let mut big = BigUint {
+            limbs: [0; LIMBS],
+            len: 0,
+        };
+        while v != 0 {
+            big.limbs[big.len] = v as u32;
+            big.len += 1;
+            v >>= 32;
+        }
+        big
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.limbs[..self.len] {
+            let t = *limb as u64 * factor as u64 + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        if carry != 0 {
+            self.limbs[self.len] = carry as u32;
+            self.len += 1;
+        }
+    }
+
+    fn mul_pow5(&mut self, mut k: usize) {
+        // 5^13 is the largest power of 5 fitting in `u32`
+        while k >= 13 {
+            self.mul_small(1_220_703_125);
+            k -= 13;
+        }
+        self.mul_small(5u32.pow(k as u32));
+    }
+
+    fn shl(&mut self, n: usize) {
+        let (limbs, bits) = (n / 32, n % 32);
+        if self.len == 0 {
+            return;
+        }
+        if bits > 0 {
+            let mut carry = 0;
+            for limb in &mut self.limbs[..self.len] {
+                let next = *limb >> (32 - bits);
+                *limb = *limb << bits | carry;
+                carry = next;
+            }
+            if carry != 0 {
+                self.limbs[self.len] = carry;
+                self.len += 1;
+            }
+        }
+        self.limbs.copy_within(..self.len, limbs);
+        self.limbs[..limbs].fill(0);
+        self.len += limbs;
+    }
+
+    // divides by `divisor` in place and returns the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs[..self.len].iter_mut().rev() {
+            let t = rem << 32 | *limb as u64;
+            *limb = (t / divisor as u64) as u32;
+            rem = t % divisor as u64;
+        }
+        while self.len > 0 && self.limbs[self.len - 1] == 0 {
+            self.len -= 1;
+        }
+        rem as u32
+    }
+}
+
+impl fmt::Display for ExactDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let HexFloatParts {
+            negative,
+            significand,
+            exponent,
+        } = self.parts;
+        if negative {
+            f.write_str("-")?;
+        }
+        let limbs = limbs_for(exponent);
+        if limbs <= SMALL_LIMBS {
+            write_decimal::<SMALL_LIMBS, { chunks_for(SMALL_LIMBS) }>(f, significand, exponent)
+        } else if limbs <= MEDIUM_LIMBS {
+            write_decimal::<MEDIUM_LIMBS, { chunks_for(MEDIUM_LIMBS) }>(f, significand, exponent)
+        } else {
+            write_decimal::<LARGE_LIMBS, { chunks_for(LARGE_LIMBS) }>(f, significand, exponent)
+        }
+    }
+}
+
+// writes the decimal expansion of non-negative `significand * 2^exponent`
+// with buffers of given sizes, which should be enough for `limbs_for(exponent)`.
+fn write_decimal<const LIMBS: usize, const CHUNKS: usize>(
+    f: &mut fmt::Formatter,
+    significand: u128,
+    exponent: isize,
+) -> fmt::Result {
+    // the value is `big / 10^nfracs`
+    let mut big = BigUint::<LIMBS>::from_u128(significand);
+    let nfracs = if exponent >= 0 {
+        big.shl(exponent as usize);
+        0
+    } else {
+        big.mul_pow5(-exponent as usize);
+        -exponent as usize
+    };
+
+    // convert to decimal in 9-digit chunks, the least significant chunk first
+    let mut chunks = [0u32; CHUNKS];
+    let mut nchunks = 0;
+    while big.len > 0 {
+        chunks[nchunks] = big.div_rem_small(1_000_000_000);
+        nchunks += 1;
+    }
+    let ndigits = match nchunks {
+        0 => 0,
+        _ => {
+            let mut top = chunks[nchunks - 1];
+            let mut n = (nchunks - 1) * 9;
+            while top > 0 {
+                top /= 10;
+                n += 1;
+            }
+            n
+        }
+    };
+
+    // `ndigits` digits with `nfracs` of them after the decimal point,
+    // where missing digits are leading zeroes
+    let digit = |i: usize| {
+        if i < ndigits {
+            chunks[i / 9] / 10u32.pow((i % 9) as u32) % 10
+        } else {
+            0
+        }
+    };
+    if ndigits > nfracs {
+        for i in (nfracs..ndigits).rev() {
+            write!(f, "{}", digit(i))?;
+        }
+    } else {
+        f.write_str("0")?;
+    }
+    if nfracs > 0 {
+        f.write_str(".")?;
+        for i in (0..nfracs).rev() {
+            write!(f, "{}", digit(i))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_hexf_decimal() {
+    use crate::ParseHexfErrorKind::*;
+    let decimal = |s| parse_hexf_decimal(s, true).unwrap().to_string();
+    let kind = |s: &str| parse_hexf_decimal(s, true).unwrap_err().kind();
+
+    assert_eq!(decimal("0x0p0"), "0");
+    assert_eq!(decimal("-0x0p0"), "-0");
+    assert_eq!(decimal("0x1p0"), "1");
+    assert_eq!(decimal("0x1_0p0"), "16");
+    assert_eq!(decimal("-0x1.8p1"), "-3");
+    assert_eq!(decimal("0x1p-1"), "0.5");
+    assert_eq!(decimal("-0x1.8p-3"), "-0.1875");
+    assert_eq!(decimal("0x1.4p3"), "10");
+    assert_eq!(decimal("0x1.5p3"), "10.5");
+    assert_eq!(decimal("0x1p-10"), "0.0009765625");
+    assert_eq!(
+        decimal("0x1.999999999999ap-4"),
+        "0.1000000000000000055511151231257827021181583404541015625"
+    );
+    assert_eq!(decimal("0x1p64"), "18446744073709551616");
+    assert_eq!(
+        decimal("0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffffp0"),
+        u128::MAX.to_string()
+    );
+    assert_eq!(
+        decimal("0x1.fffffffffffffp1023"),
+        "179769313486231570814527423731704356798070567525844996598917476803157260780028538\
+         760589558632766878171540458953514382464234321326889464182768467546703537516986049\
+         910576551282076245490090389328944075868508455133942304583236903222948165808559332\
+         123348274797826204144723168738177180919299881250404026184124858368"
+    );
+
+    let min = decimal("0x1p-16494");
+    assert!(min.starts_with("0.0000"));
+    assert_eq!(min.len(), 2 + 16494);
+    assert!(min.ends_with("5"));
+    let max = decimal("0x1.ffffffffffffffffffffffffffffp16383");
+    assert_eq!(max.len(), 4933);
+    assert!(max.starts_with("11897314953572317650"));
+
+    assert_eq!(kind("0x1p16384"), Overflow);
+    assert_eq!(kind("0x1p-16495"), Underflow);
+    assert_eq!(kind(&format!("0x10p{}", isize::MAX)), Overflow);
+    assert_eq!(kind(&format!("0x1p{}", isize::MAX as i128 + 1)), Overflow);
+    assert_eq!(kind(&format!("0x1p{}", isize::MIN)), Underflow);
+    assert_eq!(kind("0x1.8p-16494"), Inexact);
+    assert_eq!(kind("inf"), UnsupportedSpecial);
+    assert_eq!(kind("0x1.00000000000000000000000000000001p0"), Inexact);
+    assert_eq!(
+        parse_hexf_decimal("0x1.8p-16494", false)
+            .unwrap_err()
+            .offset(),
+        Some(4)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_exact_decimal_buffers() {
+    // formats with the largest buffers regardless of the exponent
+    struct Large(ExactDecimal);
+    impl fmt::Display for Large {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let HexFloatParts {
+                significand,
+                exponent,
+                ..
+            } = self.0.parts;
+            write_decimal::<LARGE_LIMBS, { chunks_for(LARGE_LIMBS) }>(f, significand, exponent)
+        }
+    }
+
+    // the widest significands around the boundaries of each buffer size
+    let limits = [
+        (SMALL_LIMBS * 32 - 128) as isize,
+        -149,
+        -150,
+        (MEDIUM_LIMBS * 32 - 128) as isize,
+        -1074,
+        -1075,
+        16383 - 127,
+        MIN_EXPONENT,
+    ];
+    for &exponent in &limits {
+        for &exponent in &[exponent - 1, exponent, exponent + 1] {
+            let parts = HexFloatParts {
+                negative: false,
+                significand: u128::MAX,
+                exponent,
+            };
+            let d = match ExactDecimal::new(parts) {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            assert_eq!(d.to_string(), Large(d).to_string());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_exact_decimal_from() {
+    let decimal = |v: f64| ExactDecimal::from_f64(v).unwrap().to_string();
+
+    assert_eq!(decimal(0.0), "0");
+    assert_eq!(decimal(-0.0), "-0");
+    assert_eq!(decimal(1e23), "99999999999999991611392");
+    assert_eq!(decimal(-2.5), "-2.5");
+    assert_eq!(decimal(f64::from_bits(1)).len(), 2 + 1074);
+    assert_eq!(
+        ExactDecimal::from_f32(0.1).unwrap().to_string(),
+        "0.100000001490116119384765625"
+    );
+    assert_eq!(ExactDecimal::from_f64(f64::NAN), None);
+    assert_eq!(ExactDecimal::from_f32(f32::NEG_INFINITY), None);
+
+    // the exact expansion should parse back to the same float
+    for state in crate::XorShift64(0x2545_f491_4f6c_dd1du64).take(1000) {
+        let v = f64::from_bits(state);
+        if v.is_finite() {
+            assert_eq!(decimal(v).parse::<f64>(), Ok(v));
+        }
+    }
+}
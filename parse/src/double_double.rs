@@ -0,0 +1,437 @@
+//! Parses hexadecimal float literals into unevaluated sums of two floats (double-doubles).
+
+use crate::{
+    parse_special, parse_truncated, round_ieee, saturate_exponent, special_ieee, ParseHexfError,
+    ParseHexfErrorKind, RoundingMode, INEXACT,
+};
+
+// splits `mantissa * 2^exponent` into the bits of `(hi, lo)` in an IEEE 754 binary interchange
+// format with given field widths, where `hi` is the nearest value (ties to even)
+// and `lo` is the exact remainder, or returns an error if the remainder is not representable.
+// `literal` should be given if the mantissa has been truncated from that (validated) literal,
+// in which case the remainder is calculated from its digits instead.
+fn split_ieee(
+    negative: bool,
+    mantissa: u128,
+    exponent: isize,
+    literal: Option<&[u8]>,
+    exponent_bits: u32,
+    fraction_bits: u32,
+) -> Result<(u128, u128), ParseHexfError> {
+    let sign = (negative as u128) << (exponent_bits + fraction_bits);
+
+    // guard the exponent with the definitely safe range as in `round_binary`,
+    // since the parser may have saturated it
+    let exponent = exponent.clamp(-0x10000, 0x10000);
+    let normalexp = exponent + (127 - mantissa.leading_zeros() as isize);
+    let bias = (1 << (exponent_bits - 1)) - 1;
+    let minexp = 1 - bias - fraction_bits as isize;
+
+    // reports that bits below `2^lsbexp` are lost, counting both `hi` and `lo`
+    let lost = |lsbexp: isize| ParseHexfError {
+        lost: Some(((normalexp - lsbexp + 1) as u32, lsbexp)),
+        ..INEXACT
+    };
+
+    let hi = match round_ieee(
+        negative,
+        mantissa | literal.is_some() as u128,
+        exponent,
+        RoundingMode::NearestTiesEven,
+        exponent_bits,
+        fraction_bits,
+    ) {
+        (hi, None) => return Ok((hi, sign)),
+        (hi, Some(ref err)) if err.kind == ParseHexfErrorKind::Inexact => hi,
+        (_, Some(err)) => return Err(err),
+    };
+
+    // decode `hi` into `himant * 2^hiexp`, which has no bits below 2^exponent
+    // because otherwise `hi` would be exact
+    let biased = (hi >> fraction_bits) as isize & ((1 << exponent_bits) - 1);
+    let fraction = hi & ((1 << fraction_bits) - 1);
+    let (himant, hiexp) = if biased == 0 {
+        (fraction, minexp)
+    } else {
+        (
+            fraction | 1 << fraction_bits,
+            biased - bias - fraction_bits as isize,
+        )
+    };
+
+    let (lonegative, lomant, loexp) = if let Some(literal) = literal {
+        match remainder(literal, himant, hiexp) {
+            Some((lonegative, lomant, loexp)) => (lonegative, lomant, loexp),
+            // the remainder alone has more significant bits than `lo` can hold
+            None => {
+                let lsbexp = normalexp - 2 * (fraction_bits as isize + 1);
+                return Err(lost(if lsbexp < minexp { minexp } else { lsbexp }));
+            }
+        }
+    } else {
+        // the remainder is at most a half ulp of `hi` and fits in `i128`,
+        // so it can be calculated modulo 2^128 (where `hi` itself may be 2^128 after the rounding)
+        let shift = (hiexp - exponent) as u32;
+        let hishifted = if shift < 128 { himant << shift } else { 0 };
+        let rest = mantissa.wrapping_sub(hishifted) as i128;
+        (negative != (rest < 0), rest.unsigned_abs(), exponent)
+    };
+    match round_ieee(
+        lonegative,
+        lomant,
+        loexp,
+        RoundingMode::TowardZero,
+        exponent_bits,
+        fraction_bits,
+    ) {
+        (lo, None) => Ok((hi, lo)),
+        // `lo` can lose bits even when it underflows, and `hi` is never out of range here,
+        // so the literal is merely inexact. the number of significant bits counts both parts.
+        (_, Some(err)) => Err(lost(match err.lost {
+            Some((_, lsbexp)) => lsbexp,
+            None => minexp,
+        })),
+    }
+}
+
+// calculates the exact remainder `literal - hi` from the digits of given validated literal,
+// where `hi = himant * 2^hiexp` is the literal rounded to the nearest,
+// so that any number of zeroes between two parts can be skipped.
+// returns the sign, mantissa and exponent of the remainder,
+// or `None` if it has more than 124 significant bits which never fit in `lo`.
+fn remainder(literal: &[u8], himant: u128, hiexp: isize) -> Option<(bool, u128, isize)> {
+    let (negative, s) = match literal {
+        [b'-', s @ ..] => (true, s),
+        [b'+', s @ ..] => (false, s),
+        s => (false, s),
+    };
+    let s = &s[2..]; // `0x`
+
+    // count digits and read the exponent to get the weight of each digit
+    let mut ndigits = 0i128;
+    let mut nintegrals = None;
+    let mut exponent = 0i128;
+    let mut negative_exponent = false;
+    let mut in_exponent = false;
+    for &c in s {
+        match c {
+            b'p' | b'P' => in_exponent = true,
+            b'-' => negative_exponent = true,
+            b'.' => nintegrals = Some(ndigits),
+            b'_' | b'+' => {}
+            _ if in_exponent => {
+                exponent = exponent
+                    .saturating_mul(10)
+                    .saturating_add((c - b'0') as i128)
+            }
+            _ => ndigits += 1,
+        }
+    }
+    let nfracs = ndigits - nintegrals.unwrap_or(ndigits);
+    if negative_exponent {
+        exponent = -exponent;
+    }
+
+    // `floor(hi / 2^weight)`, which is only needed modulo 16 below `hiexp`
+    let hi_above = |weight: i128| {
+        let shift = weight - hiexp as i128;
+        if shift >= 128 {
+            0
+        } else if shift >= 0 {
+            himant >> shift
+        } else if shift > -4 {
+            himant << -shift
+        } else {
+            0
+        }
+    };
+
+    // `acc * 2^accexp` is the remainder of digits read so far, starting from the weight above
+    // the first digit where `hi` can have a carry (it can't be larger than that)
+    let mut weight = exponent - 4 * nfracs + 4 * ndigits;
+    let mut acc = -(hi_above(weight) as i128);
+    let mut accexp = weight;
+    for &c in s {
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            b'.' | b'_' => continue,
+            _ => break,
+        };
+        weight -= 4;
+        let diff = digit as i128 - (hi_above(weight) & 15) as i128;
+        if diff == 0 {
+            continue;
+        }
+        if acc != 0 {
+            // the remainder would have bits from both `2^accexp` and `2^weight`
+            let shift = accexp - weight;
+            if shift + (128 - acc.unsigned_abs().leading_zeros() as i128) > 124 {
+                return None;
+            }
+            acc <<= shift;
+        }
+        acc += diff;
+        accexp = weight;
+    }
+    Some((
+        negative != (acc < 0),
+        acc.unsigned_abs(),
+        saturate_exponent(accexp),
+    ))
+}
+
+// parses a literal into the bits of a double-double with given field widths.
+fn parse_ieee_x2(
+    s: &str,
+    allow_underscore: bool,
+    exponent_bits: u32,
+    fraction_bits: u32,
+) -> Result<(u128, u128), ParseHexfError> {
+    let bytes = s.as_bytes();
+    if let Some((negative, special)) = parse_special(bytes, allow_underscore, fraction_bits)? {
+        let hi = special_ieee(negative, special, exponent_bits, fraction_bits);
+        return Ok((hi, 0));
+    }
+    let (negative, mantissa, exponent, sticky) = parse_truncated(bytes, allow_underscore)?;
+    split_ieee(
+        negative,
+        mantissa,
+        saturate_exponent(exponent),
+        if sticky { Some(bytes) } else { None },
+        exponent_bits,
+        fraction_bits,
+    )
+    .map_err(|err| err.locate(bytes))
+}
+
+/// Tries to parse a hexadecimal float literal to a double-double,
+/// i.e. an unevaluated sum `hi + lo` of two `f64` values.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// `hi` is the literal rounded to the nearest `f64` (ties to even),
+/// and `lo` is the exact remainder which should be also representable as `f64`.
+/// Therefore two parts never overlap, `|lo|` is at most a half ulp of `hi`,
+/// and `hi + lo` rounds back to `hi`.
+/// This can represent up to 107 significant bits, or more if some of them are zeroes
+/// (or ones for the negative `lo`) between two parts, however long the literal is.
+/// Any literal not exactly representable in this way results in an error.
+///
+/// If the literal is exactly representable as a single `f64`, `lo` is a zero with the same sign.
+/// Infinities and NaNs are also accepted and returned in `hi` with `lo` being a positive zero.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// let (hi, lo) = parse_hexf64x2("0x3.243f6a8885a308d313198a2e038p0", false).unwrap();
+/// assert_eq!(hi, std::f64::consts::PI);
+/// assert_eq!(lo, 1.2246467991473532e-16);
+/// assert_eq!(parse_hexf64x2("-0x1.8p1", false), Ok((-3.0, -0.0)));
+/// assert!(parse_hexf64x2("0x1.00000000000008000000000000001p0", false).is_err());
+/// ```
+pub fn parse_hexf64x2(s: &str, allow_underscore: bool) -> Result<(f64, f64), ParseHexfError> {
+    let (hi, lo) = parse_ieee_x2(s, allow_underscore, 11, 52)?;
+    Ok((f64::from_bits(hi as u64), f64::from_bits(lo as u64)))
+}
+
+/// Tries to parse a hexadecimal float literal to an unevaluated sum `hi + lo` of two `f32` values.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This is same to `parse_hexf64x2` but for `f32`,
+/// so it can represent up to 49 significant bits.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf32x2("0x1.000001p0", false), Ok((1.0, 5.9604645e-8)));
+/// assert_eq!(parse_hexf32x2("0x1.000003p0", false), Ok((1.0000002, -5.9604645e-8)));
+/// ```
+pub fn parse_hexf32x2(s: &str, allow_underscore: bool) -> Result<(f32, f32), ParseHexfError> {
+    let (hi, lo) = parse_ieee_x2(s, allow_underscore, 8, 23)?;
+    Ok((f32::from_bits(hi as u32), f32::from_bits(lo as u32)))
+}
+
+#[test]
+fn test_parse_hexf64x2() {
+    use crate::ParseHexfErrorKind::*;
+    let x2 = |s| parse_hexf64x2(s, true).map(|(hi, lo)| (hi.to_bits(), lo.to_bits()));
+    let kind = |s| parse_hexf64x2(s, true).unwrap_err().kind();
+
+    assert_eq!(x2("0x0p0"), Ok((0, 0)));
+    assert_eq!(x2("-0x0p0"), Ok((1 << 63, 1 << 63)));
+    assert_eq!(x2("0x1p0"), Ok((0x3ff0_0000_0000_0000, 0)));
+    assert_eq!(
+        x2("0x1.0000000000001_8p0"),
+        Ok((0x3ff0_0000_0000_0002, 0xbca0_0000_0000_0000))
+    );
+    assert_eq!(
+        x2("0x1.0000000000000_8p0"),
+        Ok((0x3ff0_0000_0000_0000, 0x3ca0_0000_0000_0000))
+    );
+    assert_eq!(
+        x2("0x1.fffffffffffff_fffffffffffffp0"),
+        Ok((0x4000_0000_0000_0000, 0xb970_0000_0000_0000))
+    );
+    assert_eq!(
+        x2("-0x1.0000000000000_fffffffffffff8p0"),
+        Ok((0xbff0_0000_0000_0001, 0x3960_0000_0000_0000))
+    );
+    // the gap between two parts doesn't count, even when the parser can't hold the whole literal
+    assert_eq!(
+        x2("0x1.0000000000000_0000000000000001p0"),
+        Ok((0x3ff0_0000_0000_0000, 0x38b0_0000_0000_0000))
+    );
+    assert_eq!(
+        x2("0x1.0000000000000_0000000000000000_0000000000001p0"),
+        Ok((0x3ff0_0000_0000_0000, 0x3570_0000_0000_0000))
+    );
+    assert_eq!(
+        x2("0x1.000000000000f_ffffffffffffffff_ffffffffffffffffp0"),
+        Ok((0x3ff0_0000_0000_0010, 0xb4b0_0000_0000_0000))
+    );
+    assert_eq!(x2("0x1.fffffffffffffp1023"), Ok((0x7fef_ffff_ffff_ffff, 0)));
+    assert_eq!(
+        x2("0x1.fffffffffffff_7ffffffffffffcp1023"),
+        Ok((0x7fef_ffff_ffff_ffff, 0x7c8f_ffff_ffff_ffff))
+    );
+    assert_eq!(
+        x2("0x1.0000000000000_0000000000001p-960"),
+        Ok((0x03f0_0000_0000_0000, 0x400))
+    );
+    assert_eq!(x2("0x1p-1074"), Ok((1, 0)));
+    assert_eq!(x2("inf"), Ok((0x7ff0_0000_0000_0000, 0)));
+    assert_eq!(x2("-nan"), Ok((0xfff8_0000_0000_0000, 0)));
+
+    assert_eq!(kind("0x1.0000000000000_8000000000000_1p0"), Inexact);
+    assert_eq!(kind("0x1.8p-1074"), Inexact);
+    assert_eq!(kind("0x1.fffffffffffff8p1023"), Overflow);
+    assert_eq!(kind("0x1p-1076"), Underflow);
+    assert_eq!(kind("0x1.0000000000000_4p-1022"), Inexact);
+    assert_eq!(
+        kind("0x1.0000000000001_0000000000000000_0000000000000000_0000001p-1000"),
+        Inexact
+    );
+    // `lo` loses bits in the same way as `parse_hexf64`
+    assert_eq!(
+        parse_hexf64x2("0x1.8p-1074", false).unwrap_err(),
+        crate::parse_hexf64("0x1.8p-1074", false).unwrap_err()
+    );
+    // saturated exponents
+    assert_eq!(kind("0xffp99999999999999999999"), Overflow);
+    assert_eq!(kind("-0xffp99999999999999999999"), Overflow);
+    assert_eq!(kind("0xffp-99999999999999999999"), Underflow);
+    assert_eq!(
+        kind("0x1.000000000000000000000000000000001p99999999999999999999"),
+        Overflow
+    );
+    assert_eq!(
+        kind("0x1.000000000000000000000000000000001p-99999999999999999999"),
+        Underflow
+    );
+
+    let err = parse_hexf64x2("0x1.00000000000008000000000000001p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(32));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 107 significant bits fit, bits from the digit at byte 32 are lost)"
+    );
+    // a truncated literal reports the precision of the double-double, not of the parser
+    let s = "0x1.000000000000001_00000000000000000000000000000001p0";
+    let err = parse_hexf64x2(s, true).unwrap_err();
+    assert_eq!(err.offset(), Some(51));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 107 significant bits fit, bits from the digit at byte 51 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexf32x2() {
+    use crate::ParseHexfErrorKind::*;
+
+    assert_eq!(parse_hexf32x2("0x1p0", false), Ok((1.0, 0.0)));
+    let (hi, lo) = parse_hexf32x2("0x1.99999999999p-4", false).unwrap();
+    assert_eq!(hi, 0.1);
+    assert_eq!(
+        hi as f64 + lo as f64,
+        crate::parse_hexf64("0x1.99999999999p-4", false).unwrap()
+    );
+    assert_eq!(parse_hexf32x2("-inf", false), Ok((f32::NEG_INFINITY, 0.0)));
+    let kind = |s| parse_hexf32x2(s, false).unwrap_err().kind();
+    assert_eq!(kind("0xffp99999999999999999999"), Overflow);
+    assert_eq!(kind("0xffp-99999999999999999999"), Underflow);
+    assert_eq!(
+        kind("0x1.000000000000000000000000000000001p99999999999999999999"),
+        Overflow
+    );
+    assert_eq!(
+        parse_hexf32x2("0x1.000000000000000000000000000000001p0", false),
+        Ok((1.0, f32::from_bits(1 << 17))) // 2^-132
+    );
+    let s = "0x1.000000000000001_00000000000000000000000000000001p0";
+    let err = parse_hexf32x2(s, true).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 49 significant bits fit, bits from the digit at byte 18 are lost)"
+    );
+    assert_eq!(
+        parse_hexf32x2("0x1.99999999999999p-4", false)
+            .unwrap_err()
+            .kind(),
+        Inexact
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_hexf64x2_exact() {
+    use crate::{parse_hexf_value, HexfValue};
+
+    // long literals with runs of zeroes and ones, checked against the exact arithmetic
+    let mut rng = crate::XorShift64(0x853c_49e6_748f_ea9bu64);
+    for _ in 0..10000 {
+        let state = rng.next().unwrap();
+        let mut s = String::from(if state & 1 == 0 { "0x1." } else { "-0x1." });
+        for _ in 0..(state >> 1) % 60 + 10 {
+            let state = rng.next().unwrap();
+            s.push(match state % 10 {
+                0..=6 => '0',
+                7 => 'f',
+                _ => b"0123456789abcdef"[(state >> 60) as usize] as char,
+            });
+        }
+        s.push_str(&format!("p{}", (state >> 32) as i16 % 1000));
+
+        let v = parse_hexf_value(&s, false).unwrap();
+        let hi = v.to_f64_rounded(RoundingMode::NearestTiesEven);
+        let rest = &v - &HexfValue::from_f64(hi).unwrap();
+        match parse_hexf64x2(&s, false) {
+            Ok((hi_, lo)) => {
+                assert_eq!(hi_, hi, "{}", s);
+                assert_eq!(HexfValue::from_f64(lo).unwrap(), rest, "{}", s);
+            }
+            Err(err) => {
+                assert_eq!(err.kind(), ParseHexfErrorKind::Inexact, "{}", s);
+                assert!(rest.to_f64().is_err(), "{}", s);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_split_ieee_sum() {
+    // `hi + lo` should be exactly the literal, checked with `f64` sums of `f32` parts
+    for state in crate::XorShift64(0x2545_f491_4f6c_dd1du64).take(10000) {
+        let mantissa = (state >> 16) as u128; // 48 bits
+        let exponent = ((state >> 56) % 200) as isize - 149;
+        let (hi, lo) = match split_ieee(false, mantissa, exponent, None, 8, 23) {
+            Ok((hi, lo)) => (f32::from_bits(hi as u32), f32::from_bits(lo as u32)),
+            Err(err) => panic!("{:x}p{}: {}", mantissa, exponent, err),
+        };
+        let expected = mantissa as f64 * 2f64.powi(exponent as i32);
+        assert_eq!(hi as f64 + lo as f64, expected);
+        assert_eq!(hi + lo, hi);
+    }
+}
@@ -0,0 +1,1093 @@
+//! Formats floats into hexadecimal float literals.
+
+use crate::{BinaryFormat, RoundingMode};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(test)]
+use crate::x87::x87;
+
+// writes an IEEE 754 binary interchange format value with given field widths.
+fn write_ieee<W: fmt::Write>(
+    w: &mut W,
+    bits: u128,
+    exponent_bits: u32,
+    fraction_bits: u32,
+) -> fmt::Result {
+    write_binary(w, bits, &BinaryFormat::ieee(exponent_bits, fraction_bits))
+}
+
+// writes a value in given binary format.
+fn write_binary<W: fmt::Write>(w: &mut W, bits: u128, format: &BinaryFormat) -> fmt::Result {
+    let exponent_bits = format.exponent_bits;
+    let fraction_bits = format.fraction_bits;
+    let negative = bits >> (exponent_bits + fraction_bits) & 1 != 0;
+    let biased = (bits >> fraction_bits) as i32 & ((1 << exponent_bits) - 1);
+    let fraction = bits & ((1 << fraction_bits) - 1);
+    let sign = if negative { "-" } else { "" };
+
+    if format.has_infinity && biased == (1 << exponent_bits) - 1 {
+        let quiet = 1 << (fraction_bits - 1);
+        return if fraction == 0 {
+            write!(w, "{}inf", sign)
+        } else if fraction == quiet {
+            write!(w, "{}nan", sign)
+        } else if fraction & quiet != 0 {
+            write!(w, "{}nan:0x{:x}", sign, fraction & !quiet)
+        } else {
+            write!(w, "{}snan:0x{:x}", sign, fraction)
+        };
+    }
+    if format.has_nan
+        && !format.has_infinity
+        && biased == (1 << exponent_bits) - 1
+        && fraction == (1 << fraction_bits) - 1
+    {
+        // the single NaN without any payload
+        return write!(w, "{}nan", sign);
+    }
+
+    // align the fraction to the hex digit boundary
+    let ndigits = fraction_bits.div_ceil(4) as usize;
+    let fraction = fraction << (ndigits as u32 * 4 - fraction_bits);
+    let (lead, exponent) = if biased > 0 {
+        (1, biased - format.bias)
+    } else if fraction > 0 {
+        (0, 1 - format.bias)
+    } else {
+        (0, 0)
+    };
+    write!(
+        w,
+        "{}0x{}.{:0width$x}p{:+}",
+        sign,
+        lead,
+        fraction,
+        exponent,
+        width = ndigits
+    )
+}
+
+// writes a value in given binary format with the fewest hex digits.
+// finite numbers are normalized to have the leading digit 1 even when subnormal,
+// so that the exponent is uniquely determined by the value.
+fn write_binary_shortest<W: fmt::Write>(
+    w: &mut W,
+    bits: u128,
+    format: &BinaryFormat,
+) -> fmt::Result {
+    let exponent_bits = format.exponent_bits;
+    let fraction_bits = format.fraction_bits;
+    let negative = bits >> (exponent_bits + fraction_bits) & 1 != 0;
+    let biased = (bits >> fraction_bits) as i32 & ((1 << exponent_bits) - 1);
+    let fraction = bits & ((1 << fraction_bits) - 1);
+    let sign = if negative { "-" } else { "" };
+
+    // infinities and NaNs have no digits to shorten
+    if format.has_infinity && biased == (1 << exponent_bits) - 1 {
+        return write_binary(w, bits, format);
+    }
+    if format.has_nan
+        && !format.has_infinity
+        && biased == (1 << exponent_bits) - 1
+        && fraction == (1 << fraction_bits) - 1
+    {
+        return write_binary(w, bits, format);
+    }
+
+    // the value is `mantissa * 2^exponent`
+    let (mantissa, exponent) = if biased > 0 {
+        (
+            fraction | 1 << fraction_bits,
+            biased - format.bias - fraction_bits as i32,
+        )
+    } else {
+        (fraction, 1 - format.bias - fraction_bits as i32)
+    };
+    if mantissa == 0 {
+        return write!(w, "{}0x0p+0", sign);
+    }
+
+    // bits below the leading 1, aligned to the hex digit boundary without trailing zero digits
+    let nbits = 127 - mantissa.leading_zeros();
+    let exponent = exponent + nbits as i32;
+    let below = mantissa & ((1 << nbits) - 1);
+    if below == 0 {
+        return write!(w, "{}0x1p{:+}", sign, exponent);
+    }
+    let ndigits = nbits.div_ceil(4);
+    let below = below << (ndigits * 4 - nbits);
+    let nzeroes = below.trailing_zeros() / 4;
+    write!(
+        w,
+        "{}0x1.{:0width$x}p{:+}",
+        sign,
+        below >> (nzeroes * 4),
+        exponent,
+        width = (ndigits - nzeroes) as usize
+    )
+}
+
+// writes `0x<leading>.<fraction>p<exponent>` for `significand` with `ndigits` hex digits
+// in the fraction and one more leading digit, rounded to `precision` digits with given
+// `rounding` mode as in C's `%.<precision>a`. a carry out of the fraction increments
+// the leading digit, and a carry out of the leading digit `f` makes it `1`
+// with the exponent increased by 4, as glibc does.
+fn write_hex_precision<W: fmt::Write>(
+    w: &mut W,
+    negative: bool,
+    significand: u128,
+    ndigits: usize,
+    mut exponent: i32,
+    precision: usize,
+    rounding: RoundingMode,
+) -> fmt::Result {
+    let mut leading = significand >> (ndigits * 4);
+    let fraction = significand & ((1 << (ndigits * 4)) - 1);
+    let sign = if negative { "-" } else { "" };
+    let (fraction, nkept) = if precision < ndigits {
+        let nbits = (ndigits - precision) as u32 * 4;
+        let mut kept = fraction >> nbits;
+        // the last digit kept, which is the leading digit if there is no fraction
+        let last = if precision > 0 { kept } else { leading };
+        let odd = last & 1 != 0;
+        let half = fraction >> (nbits - 1) & 1 != 0;
+        let sticky = fraction & ((1 << (nbits - 1)) - 1) != 0;
+        if rounding.round_up(negative, odd, half, sticky) {
+            kept += 1;
+            if kept >> (precision * 4) != 0 {
+                kept = 0;
+                leading += 1;
+                if leading == 16 {
+                    leading = 1;
+                    exponent += 4;
+                }
+            }
+        }
+        (kept, precision)
+    } else {
+        (fraction, ndigits)
+    };
+
+    write!(w, "{}0x{:x}", sign, leading)?;
+    if precision > 0 {
+        write!(w, ".{:0width$x}", fraction, width = nkept)?;
+        for _ in nkept..precision {
+            w.write_char('0')?;
+        }
+    }
+    write!(w, "p{:+}", exponent)
+}
+
+/// Writes a hexadecimal float literal for given bits of IEEE 754 binary16 (`f16`),
+/// which can be read back exactly with `parse_hexf16`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-14`, where the fraction always has all 3 digits
+/// (as in C's `%.3a`). Zeroes are written as `0x0.000p+0`.
+/// Other values are written as in `write_hexf32`.
+pub fn write_hexf16<W: fmt::Write>(w: &mut W, bits: u16) -> fmt::Result {
+    write_ieee(w, bits as u128, 5, 10)
+}
+
+/// Writes a hexadecimal float literal for given bits of bfloat16,
+/// which can be read back exactly with `parse_hexbf16`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-126`, where the fraction always has all 2 digits.
+/// Zeroes are written as `0x0.00p+0`.
+/// Other values are written as in `write_hexf32`.
+pub fn write_hexbf16<W: fmt::Write>(w: &mut W, bits: u16) -> fmt::Result {
+    write_ieee(w, bits as u128, 8, 7)
+}
+
+/// Writes a hexadecimal float literal for given bits of OCP FP8 E4M3,
+/// which can be read back exactly with `parse_hexf8e4m3`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-6`, where the fraction always has 1 digit.
+/// Zeroes are written as `0x0.0p+0`, and NaNs are written as `nan` and `-nan`.
+pub fn write_hexf8e4m3<W: fmt::Write>(w: &mut W, bits: u8) -> fmt::Result {
+    write_binary(w, bits as u128, &BinaryFormat::FP8_E4M3)
+}
+
+/// Writes a hexadecimal float literal for given bits of OCP FP8 E5M2,
+/// which can be read back exactly with `parse_hexf8e5m2`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-14`, where the fraction always has 1 digit.
+/// Zeroes are written as `0x0.0p+0`.
+/// Other values are written as in `write_hexf32`.
+pub fn write_hexf8e5m2<W: fmt::Write>(w: &mut W, bits: u8) -> fmt::Result {
+    write_binary(w, bits as u128, &BinaryFormat::FP8_E5M2)
+}
+
+/// Writes a hexadecimal float literal for given bits of IEEE 754 binary128 (`f128`),
+/// which can be read back exactly with `parse_hexf128`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-16382`, where the fraction always has all 28 digits
+/// (as in C's `%.28a`). Zeroes are written as `0x0.0000000000000000000000000000p+0`.
+/// Other values are written as in `write_hexf32`.
+pub fn write_hexf128<W: fmt::Write>(w: &mut W, bits: u128) -> fmt::Result {
+    write_ieee(w, bits, 15, 112)
+}
+
+/// Writes a hexadecimal float literal for given x87 80-bit extended precision value,
+/// stored as 10 bytes in the little endian order, which can be read back with `parse_hexf80`.
+///
+/// Since the significand has an explicit integer bit, finite numbers are written
+/// with all 64 bits of the significand as `0x<digit>.<fraction>p<exponent>`,
+/// where the fraction always has all 15 digits (as in glibc's `%.15La`).
+/// For example, 1.0 is written as `0x8.000000000000000p-3`.
+/// Subnormal numbers are written with the minimum exponent `-16385`,
+/// and zeroes are written as `0x0.000000000000000p+0`.
+///
+/// Non-canonical encodings are written with their value, which reads back to
+/// the canonical encoding of the same value instead.
+/// This includes pseudo-denormals (the zero exponent with the integer bit set)
+/// and unnormals (a non-zero exponent without the integer bit).
+/// Infinities and NaNs are written as in `write_hexf32`, where the payload excludes
+/// the integer and quiet bits; pseudo-infinities and pseudo-NaNs
+/// (the maximum exponent without the integer bit) are written in the same way.
+pub fn write_hexf80<W: fmt::Write>(w: &mut W, bytes: [u8; 10]) -> fmt::Result {
+    let significand = u64::from_le_bytes([
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+    ]);
+    let sign_exponent = u16::from_le_bytes([bytes[8], bytes[9]]);
+    let biased = (sign_exponent & 0x7fff) as i32;
+
+    if biased == 0x7fff {
+        // drop the integer bit and reuse the formatting for IEEE-like formats
+        let fraction = significand as u128 & ((1 << 63) - 1);
+        return write_ieee(w, (sign_exponent as u128) << 63 | fraction, 15, 63);
+    }
+
+    let sign = if sign_exponent & 0x8000 != 0 { "-" } else { "" };
+    // the value is `significand * 2^(max(biased, 1) - 16383 - 63)`,
+    // and the first digit contains the integer bit and the first three fraction bits
+    let exponent = if significand == 0 {
+        0
+    } else if biased == 0 {
+        1 - 16383 - 3
+    } else {
+        biased - 16383 - 3
+    };
+    write!(
+        w,
+        "{}0x{:x}.{:015x}p{:+}",
+        sign,
+        significand >> 60,
+        significand & ((1 << 60) - 1),
+        exponent
+    )
+}
+
+/// Writes a hexadecimal float literal for given x87 80-bit extended precision value
+/// with a fixed number of digits, as glibc's `printf("%.<precision>La")` does.
+///
+/// Finite numbers are written as in `write_hexf80` but the fraction is rounded to
+/// exactly `precision` digits with given `rounding` mode, and the decimal point is omitted
+/// if `precision` is zero. If the rounding carries out of the leading digit `f`,
+/// the leading digit becomes `1` and the exponent is increased by 4,
+/// e.g. `0x1.00p+1` for `0xf.fffp-3` with the precision of 2.
+/// Infinities and NaNs are written as in `write_hexf64_precision`.
+pub fn write_hexf80_precision<W: fmt::Write>(
+    w: &mut W,
+    bytes: [u8; 10],
+    precision: usize,
+    rounding: RoundingMode,
+) -> fmt::Result {
+    let significand = u64::from_le_bytes([
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+    ]);
+    let sign_exponent = u16::from_le_bytes([bytes[8], bytes[9]]);
+    let negative = sign_exponent & 0x8000 != 0;
+    let biased = (sign_exponent & 0x7fff) as i32;
+
+    if biased == 0x7fff {
+        let sign = if negative { "-" } else { "" };
+        let name = if significand << 1 == 0 { "inf" } else { "nan" };
+        return write!(w, "{}{}", sign, name);
+    }
+
+    // same to `write_hexf80`
+    let exponent = if significand == 0 {
+        0
+    } else if biased == 0 {
+        1 - 16383 - 3
+    } else {
+        biased - 16383 - 3
+    };
+    write_hex_precision(
+        w,
+        negative,
+        significand as u128,
+        15,
+        exponent,
+        precision,
+        rounding,
+    )
+}
+
+// writes an IBM HFP number `0.<fraction> * 16^(characteristic - 64)`
+// with `ndigits` hex digits in the fraction.
+fn write_hfp<W: fmt::Write>(
+    w: &mut W,
+    negative: bool,
+    characteristic: u128,
+    fraction: u128,
+    ndigits: usize,
+) -> fmt::Result {
+    let sign = if negative { "-" } else { "" };
+    let exponent = if fraction == 0 {
+        0
+    } else {
+        4 * (characteristic as i32 - 64)
+    };
+    write!(
+        w,
+        "{}0x0.{:0width$x}p{:+}",
+        sign,
+        fraction,
+        exponent,
+        width = ndigits
+    )
+}
+
+/// Writes a hexadecimal float literal for given bits of IBM HFP short format,
+/// which can be read back exactly with `parse_hexf_ibm32`.
+///
+/// Since HFP has a base-16 exponent, numbers are written as `0x0.<fraction>p<exponent>`
+/// where the fraction always has all 6 digits and the exponent is a multiple of 4.
+/// Zeroes are written as `0x0.000000p+0`.
+/// Unnormalized numbers are written with their exact values as well,
+/// which read back to the normalized bits.
+pub fn write_hexf_ibm32<W: fmt::Write>(w: &mut W, bits: u32) -> fmt::Result {
+    let bits = bits as u128;
+    write_hfp(w, bits >> 31 != 0, bits >> 24 & 0x7f, bits & 0xff_ffff, 6)
+}
+
+/// Writes a hexadecimal float literal for given bits of IBM HFP long format,
+/// which can be read back exactly with `parse_hexf_ibm64`.
+///
+/// This is same to `write_hexf_ibm32` but the fraction always has all 14 digits.
+pub fn write_hexf_ibm64<W: fmt::Write>(w: &mut W, bits: u64) -> fmt::Result {
+    let bits = bits as u128;
+    let fraction = bits & ((1 << 56) - 1);
+    write_hfp(w, bits >> 63 != 0, bits >> 56 & 0x7f, fraction, 14)
+}
+
+/// Writes a hexadecimal float literal for given bits of IBM HFP extended format,
+/// which can be read back exactly with `parse_hexf_ibm128`.
+///
+/// This is same to `write_hexf_ibm32` but the fraction always has all 28 digits.
+/// The sign and the characteristic of the low-order part (the lower 64 bits) are ignored.
+pub fn write_hexf_ibm128<W: fmt::Write>(w: &mut W, bits: u128) -> fmt::Result {
+    let fraction = (bits >> 64 & ((1 << 56) - 1)) << 56 | (bits & ((1 << 56) - 1));
+    write_hfp(w, bits >> 127 != 0, bits >> 120 & 0x7f, fraction, 28)
+}
+
+/// Writes a hexadecimal float literal for given `f32` value,
+/// which can be read back exactly with `parse_hexf32`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-126`, where the fraction always has all 6 digits
+/// (as in C's `%.6a`). Zeroes are written as `0x0.000000p+0`.
+/// The sign is always kept, including negative zeroes.
+///
+/// Infinities and NaNs are written as `inf`, `-inf`, `nan` and `-nan`.
+/// NaNs with non-default payloads are written as `nan:0x<payload>` (quiet)
+/// or `snan:0x<payload>` (signaling), where the payload excludes the quiet bit.
+pub fn write_hexf32<W: fmt::Write>(w: &mut W, v: f32) -> fmt::Result {
+    write_ieee(w, v.to_bits() as u128, 8, 23)
+}
+
+/// Writes a hexadecimal float literal for given `f64` value,
+/// which can be read back exactly with `parse_hexf64`.
+///
+/// Normal numbers are written as `0x1.<fraction>p<exponent>` and subnormal numbers
+/// as `0x0.<fraction>p-1022`, where the fraction always has all 13 digits
+/// (as in C's `%.13a`). Zeroes are written as `0x0.0000000000000p+0`.
+/// The sign is always kept, including negative zeroes.
+///
+/// Infinities and NaNs are written as `inf`, `-inf`, `nan` and `-nan`.
+/// NaNs with non-default payloads are written as `nan:0x<payload>` (quiet)
+/// or `snan:0x<payload>` (signaling), where the payload excludes the quiet bit.
+pub fn write_hexf64<W: fmt::Write>(w: &mut W, v: f64) -> fmt::Result {
+    write_ieee(w, v.to_bits() as u128, 11, 52)
+}
+
+/// Writes the shortest hexadecimal float literal for given `f32` value,
+/// which can be read back exactly with `parse_hexf32`.
+///
+/// Finite numbers are written as `0x1.<fraction>p<exponent>` with trailing zero digits
+/// of the fraction removed, or `0x1p<exponent>` if the fraction is zero.
+/// Subnormal numbers are also normalized in this way, e.g. `0x1p-149` for the smallest one,
+/// so that every number has the unique representation.
+/// Zeroes are written as `0x0p+0` and `-0x0p+0`,
+/// and infinities and NaNs are written as in `write_hexf32`.
+pub fn write_hexf32_shortest<W: fmt::Write>(w: &mut W, v: f32) -> fmt::Result {
+    write_binary_shortest(w, v.to_bits() as u128, &BinaryFormat::BINARY32)
+}
+
+/// Writes the shortest hexadecimal float literal for given `f64` value,
+/// which can be read back exactly with `parse_hexf64`.
+///
+/// This is same to `write_hexf32_shortest` but for `f64`,
+/// e.g. `0x1.999999999999ap-4` for 0.1 and `0x1p-1074` for the smallest subnormal number.
+pub fn write_hexf64_shortest<W: fmt::Write>(w: &mut W, v: f64) -> fmt::Result {
+    write_binary_shortest(w, v.to_bits() as u128, &BinaryFormat::BINARY64)
+}
+
+/// Writes a hexadecimal float literal for given `f64` value with a fixed number of digits,
+/// as C's `printf("%.<precision>a")` does.
+///
+/// The output matches glibc byte-for-byte, including the rounding mode set by `fesetround`
+/// if the same mode is given as `rounding`. Finite numbers are written as
+/// `0x1.<fraction>p<exponent>` and subnormal numbers as `0x0.<fraction>p-1022`,
+/// where the fraction has exactly `precision` digits (padded with zeroes if needed),
+/// and the decimal point is omitted if `precision` is zero. Zeroes have the exponent `+0`.
+/// If the rounding carries out of the fraction, the leading digit is incremented instead of
+/// adjusting the exponent, e.g. `0x2.00p+0` for `0x1.fffp+0` with the precision of 2.
+///
+/// Infinities are written as `inf` and `-inf`, and NaNs as `nan` and `-nan` without payloads.
+/// Therefore this doesn't always round-trip, unlike `write_hexf64`.
+pub fn write_hexf64_precision<W: fmt::Write>(
+    w: &mut W,
+    v: f64,
+    precision: usize,
+    rounding: RoundingMode,
+) -> fmt::Result {
+    let bits = v.to_bits();
+    let negative = bits >> 63 != 0;
+    let biased = (bits >> 52 & 0x7ff) as i32;
+    let fraction = (bits & ((1 << 52) - 1)) as u128;
+    let sign = if negative { "-" } else { "" };
+    if biased == 0x7ff {
+        let name = if fraction == 0 { "inf" } else { "nan" };
+        return write!(w, "{}{}", sign, name);
+    }
+    let (leading, exponent) = if biased > 0 {
+        (1, biased - 1023)
+    } else if fraction > 0 {
+        (0, -1022)
+    } else {
+        (0, 0)
+    };
+    let significand = (leading as u128) << 52 | fraction;
+    write_hex_precision(w, negative, significand, 13, exponent, precision, rounding)
+}
+
+/// Writes a hexadecimal float literal for given `f32` value with a fixed number of digits,
+/// as C's `printf("%.<precision>a")` does.
+///
+/// Since C promotes `float` arguments to `double`, this is same to `write_hexf64_precision`
+/// with the value converted to `f64`. In particular subnormal `f32` numbers are normalized.
+pub fn write_hexf32_precision<W: fmt::Write>(
+    w: &mut W,
+    v: f32,
+    precision: usize,
+    rounding: RoundingMode,
+) -> fmt::Result {
+    write_hexf64_precision(w, v as f64, precision, rounding)
+}
+
+/// Formats given bits of IEEE 754 binary16 (`f16`) into a hexadecimal float literal.
+/// See `write_hexf16` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf16(bits: u16) -> String {
+    let mut s = String::new();
+    write_hexf16(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of bfloat16 into a hexadecimal float literal.
+/// See `write_hexbf16` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexbf16(bits: u16) -> String {
+    let mut s = String::new();
+    write_hexbf16(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of OCP FP8 E4M3 into a hexadecimal float literal.
+/// See `write_hexf8e4m3` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf8e4m3(bits: u8) -> String {
+    let mut s = String::new();
+    write_hexf8e4m3(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of OCP FP8 E5M2 into a hexadecimal float literal.
+/// See `write_hexf8e5m2` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf8e5m2(bits: u8) -> String {
+    let mut s = String::new();
+    write_hexf8e5m2(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of IEEE 754 binary128 (`f128`) into a hexadecimal float literal.
+/// See `write_hexf128` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf128(bits: u128) -> String {
+    let mut s = String::new();
+    write_hexf128(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given x87 80-bit extended precision value into a hexadecimal float literal.
+/// See `write_hexf80` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf80(bytes: [u8; 10]) -> String {
+    let mut s = String::new();
+    write_hexf80(&mut s, bytes).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of IBM HFP short format into a hexadecimal float literal.
+/// See `write_hexf_ibm32` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf_ibm32(bits: u32) -> String {
+    let mut s = String::new();
+    write_hexf_ibm32(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of IBM HFP long format into a hexadecimal float literal.
+/// See `write_hexf_ibm64` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf_ibm64(bits: u64) -> String {
+    let mut s = String::new();
+    write_hexf_ibm64(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given bits of IBM HFP extended format into a hexadecimal float literal.
+/// See `write_hexf_ibm128` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf_ibm128(bits: u128) -> String {
+    let mut s = String::new();
+    write_hexf_ibm128(&mut s, bits).expect("writing to String never fails");
+    s
+}
+
+/// Formats given `f32` value into a hexadecimal float literal.
+/// See `write_hexf32` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf32(v: f32) -> String {
+    let mut s = String::new();
+    write_hexf32(&mut s, v).expect("writing to String never fails");
+    s
+}
+
+/// Formats given `f64` value into a hexadecimal float literal.
+/// See `write_hexf64` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf64(v: f64) -> String {
+    let mut s = String::new();
+    write_hexf64(&mut s, v).expect("writing to String never fails");
+    s
+}
+
+/// Formats given `f32` value into the shortest hexadecimal float literal.
+/// See `write_hexf32_shortest` for the exact format.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(format_hexf32_shortest(1.0), "0x1p+0");
+/// assert_eq!(format_hexf32_shortest(-0.75), "-0x1.8p-1");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn format_hexf32_shortest(v: f32) -> String {
+    let mut s = String::new();
+    write_hexf32_shortest(&mut s, v).expect("writing to String never fails");
+    s
+}
+
+/// Formats given `f64` value into the shortest hexadecimal float literal.
+/// See `write_hexf64_shortest` for the exact format.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(format_hexf64_shortest(0.1), "0x1.999999999999ap-4");
+/// assert_eq!(parse_hexf64(&format_hexf64_shortest(0.1), false), Ok(0.1));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn format_hexf64_shortest(v: f64) -> String {
+    let mut s = String::new();
+    write_hexf64_shortest(&mut s, v).expect("writing to String never fails");
+    s
+}
+
+/// Formats given `f32` value into a hexadecimal float literal with a fixed number of digits.
+/// See `write_hexf32_precision` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf32_precision(v: f32, precision: usize, rounding: RoundingMode) -> String {
+    let mut s = String::new();
+    write_hexf32_precision(&mut s, v, precision, rounding).expect("writing to String never fails");
+    s
+}
+
+/// Formats given `f64` value into a hexadecimal float literal with a fixed number of digits.
+/// See `write_hexf64_precision` for the exact format.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(format_hexf64_precision(0.1, 3, RoundingMode::NearestTiesEven), "0x1.99ap-4");
+/// assert_eq!(format_hexf64_precision(0.1, 3, RoundingMode::TowardZero), "0x1.999p-4");
+/// assert_eq!(format_hexf64_precision(1.99, 1, RoundingMode::NearestTiesEven), "0x2.0p+0");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn format_hexf64_precision(v: f64, precision: usize, rounding: RoundingMode) -> String {
+    let mut s = String::new();
+    write_hexf64_precision(&mut s, v, precision, rounding).expect("writing to String never fails");
+    s
+}
+
+/// Formats given x87 80-bit extended precision value into a hexadecimal float literal
+/// with a fixed number of digits. See `write_hexf80_precision` for the exact format.
+#[cfg(feature = "alloc")]
+pub fn format_hexf80_precision(
+    bytes: [u8; 10],
+    precision: usize,
+    rounding: RoundingMode,
+) -> String {
+    let mut s = String::new();
+    write_hexf80_precision(&mut s, bytes, precision, rounding)
+        .expect("writing to String never fails");
+    s
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf16() {
+    assert_eq!(format_hexf16(0x0000), "0x0.000p+0");
+    assert_eq!(format_hexf16(0x8000), "-0x0.000p+0");
+    assert_eq!(format_hexf16(0x3c00), "0x1.000p+0");
+    assert_eq!(format_hexf16(0x3555), "0x1.554p-2");
+    assert_eq!(format_hexf16(0x7bff), "0x1.ffcp+15");
+    assert_eq!(format_hexf16(0x0001), "0x0.004p-14");
+    assert_eq!(format_hexf16(0x03ff), "0x0.ffcp-14");
+    assert_eq!(format_hexf16(0xfc00), "-inf");
+    assert_eq!(format_hexf16(0x7e00), "nan");
+    assert_eq!(format_hexf16(0xfc01), "-snan:0x1");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexbf16() {
+    assert_eq!(format_hexbf16(0x0000), "0x0.00p+0");
+    assert_eq!(format_hexbf16(0x8000), "-0x0.00p+0");
+    assert_eq!(format_hexbf16(0x3f80), "0x1.00p+0");
+    assert_eq!(format_hexbf16(0x3dcd), "0x1.9ap-4");
+    assert_eq!(format_hexbf16(0x7f7f), "0x1.fep+127");
+    assert_eq!(format_hexbf16(0x0001), "0x0.02p-126");
+    assert_eq!(format_hexbf16(0xff80), "-inf");
+    assert_eq!(format_hexbf16(0x7fc0), "nan");
+    assert_eq!(format_hexbf16(0xff81), "-snan:0x1");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf8() {
+    assert_eq!(format_hexf8e4m3(0x00), "0x0.0p+0");
+    assert_eq!(format_hexf8e4m3(0x80), "-0x0.0p+0");
+    assert_eq!(format_hexf8e4m3(0x38), "0x1.0p+0");
+    assert_eq!(format_hexf8e4m3(0x01), "0x0.2p-6");
+    assert_eq!(format_hexf8e4m3(0x08), "0x1.0p-6");
+    assert_eq!(format_hexf8e4m3(0x7e), "0x1.cp+8");
+    assert_eq!(format_hexf8e4m3(0x78), "0x1.0p+8");
+    assert_eq!(format_hexf8e4m3(0x7f), "nan");
+    assert_eq!(format_hexf8e4m3(0xff), "-nan");
+
+    assert_eq!(format_hexf8e5m2(0x00), "0x0.0p+0");
+    assert_eq!(format_hexf8e5m2(0x3c), "0x1.0p+0");
+    assert_eq!(format_hexf8e5m2(0x01), "0x0.4p-14");
+    assert_eq!(format_hexf8e5m2(0x7b), "0x1.cp+15");
+    assert_eq!(format_hexf8e5m2(0xfc), "-inf");
+    assert_eq!(format_hexf8e5m2(0x7e), "nan");
+    assert_eq!(format_hexf8e5m2(0x7f), "nan:0x1");
+    assert_eq!(format_hexf8e5m2(0x7d), "snan:0x1");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf128() {
+    assert_eq!(format_hexf128(0), "0x0.0000000000000000000000000000p+0");
+    assert_eq!(
+        format_hexf128(0x3fff << 112),
+        "0x1.0000000000000000000000000000p+0"
+    );
+    assert_eq!(
+        format_hexf128(0x3ffb_9999_9999_9999_9999_9999_9999_999a),
+        "0x1.999999999999999999999999999ap-4"
+    );
+    assert_eq!(
+        format_hexf128(0xfffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff),
+        "-0x1.ffffffffffffffffffffffffffffp+16383"
+    );
+    assert_eq!(format_hexf128(1), "0x0.0000000000000000000000000001p-16382");
+    assert_eq!(format_hexf128(0xffff << 112), "-inf");
+    assert_eq!(format_hexf128(0x7fff_8000 << 96), "nan");
+    assert_eq!(format_hexf128(0x7fff << 112 | 1), "snan:0x1");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf80() {
+    assert_eq!(format_hexf80(x87(0, 0)), "0x0.000000000000000p+0");
+    assert_eq!(format_hexf80(x87(0x8000, 0)), "-0x0.000000000000000p+0");
+    assert_eq!(
+        format_hexf80(x87(0x3fff, 0x8000_0000_0000_0000)),
+        "0x8.000000000000000p-3"
+    );
+    assert_eq!(
+        format_hexf80(x87(0x3ffb, 0xcccc_cccc_cccc_cccd)),
+        "0xc.ccccccccccccccdp-7"
+    );
+    assert_eq!(
+        format_hexf80(x87(0xfffe, 0xffff_ffff_ffff_ffff)),
+        "-0xf.fffffffffffffffp+16380"
+    );
+    assert_eq!(format_hexf80(x87(0, 1)), "0x0.000000000000001p-16385");
+    assert_eq!(format_hexf80(x87(0xffff, 0x8000_0000_0000_0000)), "-inf");
+    assert_eq!(format_hexf80(x87(0x7fff, 0xc000_0000_0000_0000)), "nan");
+    assert_eq!(
+        format_hexf80(x87(0x7fff, 0x8000_0000_0000_0001)),
+        "snan:0x1"
+    );
+
+    // non-canonical encodings
+    assert_eq!(
+        format_hexf80(x87(0x0000, 0x8000_0000_0000_0000)),
+        "0x8.000000000000000p-16385"
+    );
+    assert_eq!(
+        format_hexf80(x87(0x3fff, 0x4000_0000_0000_0000)),
+        "0x4.000000000000000p-3"
+    );
+    assert_eq!(format_hexf80(x87(0x7fff, 0)), "inf");
+    assert_eq!(format_hexf80(x87(0x7fff, 0x4000_0000_0000_0000)), "nan");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf_ibm() {
+    assert_eq!(format_hexf_ibm32(0x0000_0000), "0x0.000000p+0");
+    assert_eq!(format_hexf_ibm32(0x8000_0000), "-0x0.000000p+0");
+    assert_eq!(format_hexf_ibm32(0x4110_0000), "0x0.100000p+4");
+    assert_eq!(format_hexf_ibm32(0xc276_a000), "-0x0.76a000p+8");
+    assert_eq!(format_hexf_ibm32(0x7fff_ffff), "0x0.ffffffp+252");
+    assert_eq!(format_hexf_ibm32(0x0010_0000), "0x0.100000p-256");
+    // unnormalized
+    assert_eq!(format_hexf_ibm32(0x4200_0001), "0x0.000001p+8");
+
+    assert_eq!(
+        format_hexf_ibm64(0x4019_9999_9999_9999),
+        "0x0.19999999999999p+0"
+    );
+    assert_eq!(
+        format_hexf_ibm128(0xc110_0000_0000_0000_b300_0000_0000_0001),
+        "-0x0.1000000000000000000000000001p+4"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf32() {
+    assert_eq!(format_hexf32(0.0), "0x0.000000p+0");
+    assert_eq!(format_hexf32(-0.0), "-0x0.000000p+0");
+    assert_eq!(format_hexf32(1.0), "0x1.000000p+0");
+    assert_eq!(format_hexf32(-1.5), "-0x1.800000p+0");
+    assert_eq!(format_hexf32(0.1), "0x1.99999ap-4");
+    assert_eq!(format_hexf32(f32::MAX), "0x1.fffffep+127");
+    assert_eq!(format_hexf32(f32::MIN_POSITIVE), "0x1.000000p-126");
+    assert_eq!(format_hexf32(f32::from_bits(1)), "0x0.000002p-126");
+    assert_eq!(format_hexf32(f32::from_bits(0x7f_ffff)), "0x0.fffffep-126");
+    assert_eq!(format_hexf32(f32::INFINITY), "inf");
+    assert_eq!(format_hexf32(f32::NEG_INFINITY), "-inf");
+    assert_eq!(format_hexf32(f32::NAN), "nan");
+    assert_eq!(format_hexf32(f32::from_bits(0x7fc0_0001)), "nan:0x1");
+    assert_eq!(format_hexf32(f32::from_bits(0xff80_0001)), "-snan:0x1");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf64() {
+    assert_eq!(format_hexf64(0.0), "0x0.0000000000000p+0");
+    assert_eq!(format_hexf64(-0.0), "-0x0.0000000000000p+0");
+    assert_eq!(format_hexf64(1.0), "0x1.0000000000000p+0");
+    assert_eq!(format_hexf64(-1.5), "-0x1.8000000000000p+0");
+    assert_eq!(format_hexf64(0.1), "0x1.999999999999ap-4");
+    assert_eq!(format_hexf64(f64::MAX), "0x1.fffffffffffffp+1023");
+    assert_eq!(format_hexf64(f64::MIN_POSITIVE), "0x1.0000000000000p-1022");
+    assert_eq!(format_hexf64(f64::from_bits(1)), "0x0.0000000000001p-1022");
+    assert_eq!(format_hexf64(f64::INFINITY), "inf");
+    assert_eq!(format_hexf64(f64::NEG_INFINITY), "-inf");
+    assert_eq!(format_hexf64(-f64::NAN), "-nan");
+    assert_eq!(
+        format_hexf64(f64::from_bits(0x7fff_ffff_ffff_ffff)),
+        "nan:0x7ffffffffffff"
+    );
+    assert_eq!(
+        format_hexf64(f64::from_bits(0xfff0_0000_0000_0001)),
+        "-snan:0x1"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf_shortest() {
+    assert_eq!(format_hexf32_shortest(0.0), "0x0p+0");
+    assert_eq!(format_hexf32_shortest(-0.0), "-0x0p+0");
+    assert_eq!(format_hexf32_shortest(1.0), "0x1p+0");
+    assert_eq!(format_hexf32_shortest(-1.5), "-0x1.8p+0");
+    assert_eq!(format_hexf32_shortest(0.1), "0x1.99999ap-4");
+    assert_eq!(format_hexf32_shortest(f32::MAX), "0x1.fffffep+127");
+    assert_eq!(format_hexf32_shortest(f32::from_bits(1)), "0x1p-149");
+    assert_eq!(format_hexf32_shortest(f32::from_bits(3)), "0x1.8p-148");
+    assert_eq!(
+        format_hexf32_shortest(f32::from_bits(0x7f_ffff)),
+        "0x1.fffffcp-127"
+    );
+    assert_eq!(format_hexf32_shortest(f32::NEG_INFINITY), "-inf");
+    assert_eq!(
+        format_hexf32_shortest(f32::from_bits(0x7fc0_0001)),
+        "nan:0x1"
+    );
+
+    assert_eq!(format_hexf64_shortest(0.0), "0x0p+0");
+    assert_eq!(format_hexf64_shortest(-0.0), "-0x0p+0");
+    assert_eq!(format_hexf64_shortest(1.0), "0x1p+0");
+    assert_eq!(format_hexf64_shortest(-0.75), "-0x1.8p-1");
+    assert_eq!(format_hexf64_shortest(1.0625), "0x1.1p+0");
+    assert_eq!(
+        format_hexf64_shortest(1.0 + f64::EPSILON),
+        "0x1.0000000000001p+0"
+    );
+    assert_eq!(format_hexf64_shortest(0.1), "0x1.999999999999ap-4");
+    assert_eq!(format_hexf64_shortest(f64::MAX), "0x1.fffffffffffffp+1023");
+    assert_eq!(format_hexf64_shortest(f64::MIN_POSITIVE), "0x1p-1022");
+    assert_eq!(format_hexf64_shortest(f64::from_bits(1)), "0x1p-1074");
+    assert_eq!(
+        format_hexf64_shortest(f64::from_bits(0xf_ffff_ffff_ffff)),
+        "0x1.ffffffffffffep-1023"
+    );
+    assert_eq!(format_hexf64_shortest(f64::INFINITY), "inf");
+    assert_eq!(format_hexf64_shortest(-f64::NAN), "-nan");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_hexf_precision() {
+    use crate::RoundingMode::*;
+    let f64p = |v, precision| format_hexf64_precision(v, precision, NearestTiesEven);
+
+    assert_eq!(f64p(0.0, 0), "0x0p+0");
+    assert_eq!(f64p(-0.0, 3), "-0x0.000p+0");
+    assert_eq!(f64p(1.0, 0), "0x1p+0");
+    assert_eq!(f64p(1.0, 20), "0x1.00000000000000000000p+0");
+    assert_eq!(f64p(0.1, 13), "0x1.999999999999ap-4");
+    assert_eq!(f64p(0.1, 3), "0x1.99ap-4");
+    assert_eq!(f64p(0.1, 0), "0x2p-4");
+    assert_eq!(f64p(-1.5, 0), "-0x2p+0");
+    // ties to even, where the leading digit counts if there is no fraction
+    assert_eq!(f64p(1.03125, 1), "0x1.0p+0");
+    assert_eq!(f64p(1.09375, 1), "0x1.2p+0");
+    assert_eq!(f64p(1.5, 0), "0x2p+0");
+    assert_eq!(f64p(0.75, 0), "0x2p-1");
+    assert_eq!(f64p(1.5000000000000002, 0), "0x2p+0");
+    // carries into the leading digit without changing the exponent
+    assert_eq!(f64p(1.9999, 2), "0x2.00p+0");
+    assert_eq!(f64p(f64::MAX, 1), "0x2.0p+1023");
+    assert_eq!(f64p(f64::from_bits(1), 3), "0x0.000p-1022");
+    assert_eq!(f64p(f64::from_bits(0xf_ffff_ffff_ffff), 3), "0x1.000p-1022");
+    assert_eq!(f64p(f64::NEG_INFINITY, 3), "-inf");
+    assert_eq!(f64p(f64::from_bits(0xfff0_0000_0000_0001), 3), "-nan");
+
+    assert_eq!(format_hexf64_precision(0.1, 3, TowardZero), "0x1.999p-4");
+    assert_eq!(
+        format_hexf64_precision(0.1, 3, TowardPositive),
+        "0x1.99ap-4"
+    );
+    assert_eq!(
+        format_hexf64_precision(-0.1, 3, TowardPositive),
+        "-0x1.999p-4"
+    );
+    assert_eq!(
+        format_hexf64_precision(-0.1, 3, TowardNegative),
+        "-0x1.99ap-4"
+    );
+    assert_eq!(
+        format_hexf64_precision(1.03125, 1, NearestTiesAway),
+        "0x1.1p+0"
+    );
+    assert_eq!(format_hexf64_precision(1.0625, 0, ToOdd), "0x1p+0");
+    assert_eq!(format_hexf64_precision(2.125, 0, ToOdd), "0x1p+1");
+    assert_eq!(format_hexf64_precision(1.0625, 0, TowardPositive), "0x2p+0");
+
+    // `f32` values are formatted as `f64` as in C
+    assert_eq!(
+        format_hexf32_precision(0.1, 6, NearestTiesEven),
+        "0x1.99999ap-4"
+    );
+    assert_eq!(
+        format_hexf32_precision(0.1, 8, NearestTiesEven),
+        "0x1.99999a00p-4"
+    );
+    assert_eq!(
+        format_hexf32_precision(f32::from_bits(1), 0, NearestTiesEven),
+        "0x1p-149"
+    );
+
+    // carries out of the leading digit `f` into the exponent
+    let x87p = |sign_exponent, significand, precision| {
+        format_hexf80_precision(x87(sign_exponent, significand), precision, NearestTiesEven)
+    };
+    assert_eq!(x87p(0x3fff, 1 << 63, 2), "0x8.00p-3");
+    assert_eq!(x87p(0x3fff, 0xffff_ffff_ffff_ffff, 2), "0x1.00p+1");
+    assert_eq!(x87p(0xbfff, 0xf800_0000_0000_0000, 0), "-0x1p+1");
+    assert_eq!(x87p(0x3fff, 0x9800_0000_0000_0000, 0), "0xap-3");
+    assert_eq!(x87p(0x0000, 0x7fff_ffff_ffff_ffff, 1), "0x8.0p-16385");
+    assert_eq!(x87p(0x0000, 0, 1), "0x0.0p+0");
+    assert_eq!(x87p(0x7fff, 1 << 63, 1), "inf");
+    assert_eq!(x87p(0xffff, 0xc000_0000_0000_0000, 1), "-nan");
+}
+
+#[test]
+fn test_write_hexf_roundtrip() {
+    // a fixed-size buffer to test in no_std as well
+    struct Buf([u8; 64], usize);
+    impl fmt::Write for Buf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.1 + s.len();
+            self.0
+                .get_mut(self.1..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.1 = end;
+            Ok(())
+        }
+    }
+    impl Buf {
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.0[..self.1]).unwrap()
+        }
+    }
+
+    // pseudorandom bit patterns to cover a wide range
+    for state in crate::XorShift64(0x9e37_79b9_7f4a_7c15u64).take(10000) {
+        for &bits in &[
+            state,
+            state >> 12,
+            state & 0x800f_ffff_ffff_ffff,
+            state | 0x7ff0_0000_0000_0001, // NaNs
+        ] {
+            let mut buf = Buf([0; 64], 0);
+            write_hexf64(&mut buf, f64::from_bits(bits)).unwrap();
+            let parsed = crate::parse_hexf64(buf.as_str(), false);
+            assert_eq!(parsed.map(f64::to_bits), Ok(bits), "{}", buf.as_str());
+
+            let mut buf = Buf([0; 64], 0);
+            write_hexf64_shortest(&mut buf, f64::from_bits(bits)).unwrap();
+            let parsed = crate::parse_hexf64(buf.as_str(), false);
+            assert_eq!(parsed.map(f64::to_bits), Ok(bits), "{}", buf.as_str());
+        }
+
+        for &bits in &[state as u16, (state >> 48) as u16 | 0x7c01] {
+            let mut buf = Buf([0; 64], 0);
+            write_hexf16(&mut buf, bits).unwrap();
+            let parsed = crate::parse_hexf16(buf.as_str(), false);
+            assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+        }
+
+        let wide = (state as u128) << 64 | (state >> 3) as u128;
+        for &bits in &[wide, wide >> 16, wide | 0x7fff << 112] {
+            let mut buf = Buf([0; 64], 0);
+            write_hexf128(&mut buf, bits).unwrap();
+            let parsed = crate::parse_hexf128(buf.as_str(), false);
+            assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+        }
+
+        // only canonical encodings, i.e. the integer bit is set iff the exponent is not zero
+        for &sign_exponent in &[(state >> 48) as u16, (state >> 56) as u16, 0x7fff, 0x8000] {
+            let integer = if sign_exponent & 0x7fff != 0 {
+                1 << 63
+            } else {
+                0
+            };
+            let significand = state.rotate_left(19) & !(1 << 63) | integer;
+            let bytes = x87(sign_exponent, significand);
+            let mut buf = Buf([0; 64], 0);
+            write_hexf80(&mut buf, bytes).unwrap();
+            let parsed = crate::parse_hexf80(buf.as_str(), false);
+            assert_eq!(parsed, Ok(bytes), "{}", buf.as_str());
+        }
+
+        for &bits in &[(state >> 16) as u16, (state >> 32) as u16 | 0x7f81] {
+            let mut buf = Buf([0; 64], 0);
+            write_hexbf16(&mut buf, bits).unwrap();
+            let parsed = crate::parse_hexbf16(buf.as_str(), false);
+            assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+        }
+
+        for &bits in &[
+            state as u32,
+            (state >> 41) as u32,
+            state as u32 & 0x807f_ffff,
+            state as u32 | 0x7f80_0001, // NaNs
+        ] {
+            let mut buf = Buf([0; 64], 0);
+            write_hexf32(&mut buf, f32::from_bits(bits)).unwrap();
+            let parsed = crate::parse_hexf32(buf.as_str(), false);
+            assert_eq!(parsed.map(f32::to_bits), Ok(bits), "{}", buf.as_str());
+
+            let mut buf = Buf([0; 64], 0);
+            write_hexf32_shortest(&mut buf, f32::from_bits(bits)).unwrap();
+            let parsed = crate::parse_hexf32(buf.as_str(), false);
+            assert_eq!(parsed.map(f32::to_bits), Ok(bits), "{}", buf.as_str());
+        }
+    }
+
+    // every bit pattern of FP8 formats
+    for bits in 0..=255u8 {
+        let mut buf = Buf([0; 64], 0);
+        write_hexf8e4m3(&mut buf, bits).unwrap();
+        let parsed = crate::parse_hexf8e4m3(buf.as_str(), false);
+        assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+
+        let mut buf = Buf([0; 64], 0);
+        write_hexf8e5m2(&mut buf, bits).unwrap();
+        let parsed = crate::parse_hexf8e5m2(buf.as_str(), false);
+        assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+    }
+
+    // normalized IBM HFP numbers
+    for state in crate::XorShift64(0x4f6c_dd1d_2545_f491u64).take(10000) {
+        let bits = state as u32 | 0x0010_0000;
+        let mut buf = Buf([0; 64], 0);
+        write_hexf_ibm32(&mut buf, bits).unwrap();
+        let parsed = crate::parse_hexf_ibm32(buf.as_str(), false);
+        assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+
+        let bits = state | 0x0010_0000_0000_0000;
+        let mut buf = Buf([0; 64], 0);
+        write_hexf_ibm64(&mut buf, bits).unwrap();
+        let parsed = crate::parse_hexf_ibm64(buf.as_str(), false);
+        assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+
+        // the low-order part has the same sign and the characteristic 14 less (modulo 128)
+        let high = bits as u128;
+        let characteristic = (high >> 56 & 0x7f).wrapping_sub(14) & 0x7f;
+        let fraction = state.rotate_left(29) as u128 & ((1 << 56) - 1);
+        let bits = high << 64 | (high & (1 << 63)) | characteristic << 56 | fraction;
+        let mut buf = Buf([0; 64], 0);
+        write_hexf_ibm128(&mut buf, bits).unwrap();
+        let parsed = crate::parse_hexf_ibm128(buf.as_str(), false);
+        assert_eq!(parsed, Ok(bits), "{}", buf.as_str());
+    }
+}
@@ -0,0 +1,192 @@
+//! Parses hexadecimal float literals into OCP 8-bit floating point (FP8) values.
+
+use crate::{parse_hexf_as, parse_hexf_as_rounded, BinaryFormat, ParseHexfError, RoundingMode};
+
+/// Tries to parse a hexadecimal float literal to the bits of OCP FP8 E4M3.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// E4M3 has 4 exponent bits (with the bias of 7) and 3 fraction bits.
+/// It has no infinities and only one NaN encoding per sign (`0x7f` and `0xff`),
+/// so the largest finite value is extended to `0x1.cp8` (448) and the smallest subnormal
+/// value is `0x1p-9`. Any literal outside of these encodable values is rejected:
+/// infinities result in `ParseHexfErrorKind::UnsupportedSpecial`,
+/// and NaNs other than `nan` (i.e. with a payload) in `ParseHexfErrorKind::NanPayloadOverflow`.
+/// Otherwise the literal should be exactly representable as in `parse_hexf32`.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf8e4m3("0x1.cp8", false), Ok(0x7e));
+/// assert_eq!(parse_hexf8e4m3("-0x1p-9", false), Ok(0x81));
+/// assert_eq!(parse_hexf8e4m3("-nan", false), Ok(0xff));
+/// assert!(parse_hexf8e4m3("0x1.ep8", false).is_err());
+/// assert!(parse_hexf8e4m3("inf", false).is_err());
+/// ```
+pub fn parse_hexf8e4m3(s: &str, allow_underscore: bool) -> Result<u8, ParseHexfError> {
+    parse_hexf_as(s, allow_underscore, &BinaryFormat::FP8_E4M3).map(|bits| bits as u8)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of OCP FP8 E4M3,
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Since E4M3 has no infinities, overflowing values always result in
+/// the largest finite value with the same sign (i.e. the saturating conversion).
+/// NaNs are accepted as in `parse_hexf8e4m3` and never rounded.
+pub fn parse_hexf8e4m3_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<u8, ParseHexfError> {
+    parse_hexf_as_rounded(s, allow_underscore, &BinaryFormat::FP8_E4M3, rounding)
+        .map(|bits| bits as u8)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of OCP FP8 E5M2.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// E5M2 has 5 exponent bits (with the bias of 15) and 2 fraction bits,
+/// and follows IEEE 754 conventions including infinities and NaNs.
+/// The largest finite value is `0x1.cp15` (57344) and the smallest subnormal value is `0x1p-16`.
+/// As with `parse_hexf32`, the literal should be exactly representable,
+/// and infinities and NaNs (optionally with a 1-bit payload) are also accepted.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf8e5m2("0x1.cp15", false), Ok(0x7b));
+/// assert_eq!(parse_hexf8e5m2("-inf", false), Ok(0xfc));
+/// assert_eq!(parse_hexf8e5m2("nan", false), Ok(0x7e));
+/// assert!(parse_hexf8e5m2("0x1p16", false).is_err());
+/// ```
+pub fn parse_hexf8e5m2(s: &str, allow_underscore: bool) -> Result<u8, ParseHexfError> {
+    parse_hexf_as(s, allow_underscore, &BinaryFormat::FP8_E5M2).map(|bits| bits as u8)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of OCP FP8 E5M2,
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexf8e5m2` and never rounded.
+pub fn parse_hexf8e5m2_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<u8, ParseHexfError> {
+    parse_hexf_as_rounded(s, allow_underscore, &BinaryFormat::FP8_E5M2, rounding)
+        .map(|bits| bits as u8)
+}
+
+#[test]
+fn test_parse_hexf8e4m3() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf8e4m3(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf8e4m3("0x0p0", false), Ok(0x00));
+    assert_eq!(parse_hexf8e4m3("-0x0p0", false), Ok(0x80));
+    assert_eq!(parse_hexf8e4m3("0x1p0", false), Ok(0x38));
+    assert_eq!(parse_hexf8e4m3("-0x1.8p1", false), Ok(0xc4));
+    assert_eq!(parse_hexf8e4m3("0x1p-6", false), Ok(0x08));
+    assert_eq!(parse_hexf8e4m3("0x0.ep-6", false), Ok(0x07));
+    assert_eq!(parse_hexf8e4m3("0x1p-9", false), Ok(0x01));
+    // the largest biased exponent is also used for finite values
+    assert_eq!(parse_hexf8e4m3("0x1p8", false), Ok(0x78));
+    assert_eq!(parse_hexf8e4m3("0x1.cp8", false), Ok(0x7e));
+    assert_eq!(parse_hexf8e4m3("-0x1_c0p0", true), Ok(0xfe));
+    assert_eq!(parse_hexf8e4m3("nan", false), Ok(0x7f));
+    assert_eq!(parse_hexf8e4m3("-NaN", false), Ok(0xff));
+
+    // `0x1.ep8` would be encoded to the NaN
+    assert_eq!(kind("0x1.ep8"), Overflow);
+    assert_eq!(kind("0x1p9"), Overflow);
+    assert_eq!(kind("0x1p-10"), Underflow);
+    assert_eq!(kind("0x1.1p0"), Inexact);
+    assert_eq!(kind("inf"), UnsupportedSpecial);
+    assert_eq!(kind("-infinity"), UnsupportedSpecial);
+    assert_eq!(kind("nan:0x1"), NanPayloadOverflow);
+    assert_eq!(kind("snan:0x1"), NanPayloadOverflow);
+}
+
+#[test]
+fn test_parse_hexf8e4m3_rounded() {
+    use crate::RoundingMode::*;
+
+    assert_eq!(
+        parse_hexf8e4m3_rounded("0x1.1p0", false, NearestTiesEven),
+        Ok(0x38)
+    );
+    assert_eq!(
+        parse_hexf8e4m3_rounded("0x1.1p0", false, TowardPositive),
+        Ok(0x39)
+    );
+    // ties to the largest finite value, which is even
+    assert_eq!(
+        parse_hexf8e4m3_rounded("0x1.dp8", false, NearestTiesEven),
+        Ok(0x7e)
+    );
+    // saturates instead of the NaN
+    assert_eq!(
+        parse_hexf8e4m3_rounded("0x1.ep8", false, NearestTiesEven),
+        Ok(0x7e)
+    );
+    assert_eq!(
+        parse_hexf8e4m3_rounded("-0x1p100", false, TowardNegative),
+        Ok(0xfe)
+    );
+    assert_eq!(
+        parse_hexf8e4m3_rounded("0x1p-10", false, NearestTiesEven),
+        Ok(0x00)
+    );
+    assert_eq!(
+        parse_hexf8e4m3_rounded("0x1p-10", false, TowardPositive),
+        Ok(0x01)
+    );
+    assert!(parse_hexf8e4m3_rounded("inf", false, TowardZero).is_err());
+}
+
+#[test]
+fn test_parse_hexf8e5m2() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf8e5m2(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf8e5m2("0x0p0", false), Ok(0x00));
+    assert_eq!(parse_hexf8e5m2("-0x0p0", false), Ok(0x80));
+    assert_eq!(parse_hexf8e5m2("0x1p0", false), Ok(0x3c));
+    assert_eq!(parse_hexf8e5m2("-0x1.8p1", false), Ok(0xc2));
+    assert_eq!(parse_hexf8e5m2("0x1p-14", false), Ok(0x04));
+    assert_eq!(parse_hexf8e5m2("0x1p-16", false), Ok(0x01));
+    assert_eq!(parse_hexf8e5m2("0x1.cp15", false), Ok(0x7b));
+    assert_eq!(parse_hexf8e5m2("inf", false), Ok(0x7c));
+    assert_eq!(parse_hexf8e5m2("-inf", false), Ok(0xfc));
+    assert_eq!(parse_hexf8e5m2("nan", false), Ok(0x7e));
+    assert_eq!(parse_hexf8e5m2("nan:0x1", false), Ok(0x7f));
+    assert_eq!(parse_hexf8e5m2("snan:0x1", false), Ok(0x7d));
+
+    assert_eq!(kind("0x1p16"), Overflow);
+    assert_eq!(kind("0x1p-17"), Underflow);
+    assert_eq!(kind("0x1.2p0"), Inexact);
+    assert_eq!(kind("nan:0x4"), NanPayloadOverflow);
+    assert_eq!(kind("snan:0x0"), ZeroSignalingNan);
+}
+
+#[test]
+fn test_parse_hexf8e5m2_rounded() {
+    use crate::RoundingMode::*;
+
+    assert_eq!(
+        parse_hexf8e5m2_rounded("0x1.2p0", false, NearestTiesEven),
+        Ok(0x3c)
+    );
+    assert_eq!(
+        parse_hexf8e5m2_rounded("0x1.6p0", false, NearestTiesEven),
+        Ok(0x3e)
+    );
+    assert_eq!(
+        parse_hexf8e5m2_rounded("0x1.ep15", false, NearestTiesEven),
+        Ok(0x7c)
+    );
+    assert_eq!(
+        parse_hexf8e5m2_rounded("-0x1p16", false, TowardZero),
+        Ok(0xfb)
+    );
+}
@@ -0,0 +1,243 @@
+//! Parses hexadecimal float literals into IBM hexadecimal floating point (HFP) formats.
+
+use crate::{is_special, parse, ParseHexfError, INEXACT, OVERFLOW, UNDERFLOW, UNSUPPORTED_SPECIAL};
+
+// encodes `mantissa * 2^exponent` to a normalized HFP number with `ndigits` hex digits
+// in the fraction, and returns its 7-bit characteristic and fraction.
+//
+// HFP numbers are `0.<fraction> * 16^(characteristic - 64)` where the fraction is normalized
+// to have a non-zero leading hex digit, so the number of significant bits varies from
+// `4 * ndigits - 3` to `4 * ndigits` depending on the leading digit (the precision wobble).
+// there are no subnormal numbers, infinities or NaNs.
+const fn encode_hfp(
+    mantissa: u128,
+    exponent: isize,
+    ndigits: u32,
+) -> Result<(u128, u128), ParseHexfError> {
+    if mantissa == 0 {
+        return Ok((0, 0));
+    }
+
+    // guard the exponent with the definitely safe range as in `round_binary`
+    let exponent = if exponent < -0x10000 {
+        -0x10000
+    } else if exponent > 0x10000 {
+        0x10000
+    } else {
+        exponent
+    };
+
+    // the number is in [2^normalexp, 2^(normalexp+1)) and also in [16^(hexexp-1), 16^hexexp)
+    let normalexp = exponent + (127 - mantissa.leading_zeros() as isize);
+    let hexexp = normalexp.div_euclid(4) + 1;
+    let characteristic = hexexp + 64;
+    if characteristic > 127 {
+        return Err(OVERFLOW);
+    }
+    if characteristic < 0 {
+        return Err(UNDERFLOW);
+    }
+
+    // the fraction has its least significant bit at 2^lsbexp,
+    // which is always below the most significant bit of the mantissa
+    let lsbexp = 4 * (hexexp - ndigits as isize);
+    let fraction = if exponent < lsbexp {
+        let shift = lsbexp - exponent;
+        if mantissa & ((1 << shift) - 1) != 0 {
+            let precision = (normalexp - lsbexp + 1) as u32;
+            return Err(ParseHexfError {
+                lost: Some((precision, lsbexp)),
+                ..INEXACT
+            });
+        }
+        mantissa >> shift
+    } else {
+        mantissa << (exponent - lsbexp)
+    };
+    Ok((characteristic as u128, fraction))
+}
+
+// parses a literal into the sign, the characteristic and the fraction of a normalized HFP number
+// with `ndigits` hex digits in the fraction.
+fn parse_hfp(
+    s: &str,
+    allow_underscore: bool,
+    ndigits: u32,
+) -> Result<(bool, u128, u128), ParseHexfError> {
+    // HFP has no special values, so their payloads are not even validated
+    if is_special(s.as_bytes()) {
+        return Err(UNSUPPORTED_SPECIAL);
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    match encode_hfp(mantissa, exponent, ndigits) {
+        Ok((characteristic, fraction)) => Ok((negative, characteristic, fraction)),
+        Err(err) => Err(err.locate(s.as_bytes())),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IBM HFP short format.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// The short format has the sign bit, the 7-bit characteristic (a base-16 exponent biased by 64)
+/// and 6 hex digits of the fraction, representing `0.<fraction> * 16^(characteristic - 64)`.
+/// The result is always normalized, i.e. the leading hex digit of the fraction is not zero
+/// unless the literal is zero, and therefore only 21 to 24 significant bits are available
+/// depending on the leading digit. The literal should be exactly representable in this way,
+/// and should be in the range of `0x1p-260` to `0x0.ffffffp252` in its magnitude.
+/// There are no infinities or NaNs in HFP, so they result in `ParseHexfErrorKind::UnsupportedSpecial`.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf_ibm32("0x1p0", false), Ok(0x4110_0000));
+/// assert_eq!(parse_hexf_ibm32("-0x76.ap0", false), Ok(0xc276_a000));
+/// assert!(parse_hexf_ibm32("0x1.000001p0", false).is_err());
+/// assert!(parse_hexf_ibm32("0xf.000001p0", false).is_err());
+/// ```
+pub fn parse_hexf_ibm32(s: &str, allow_underscore: bool) -> Result<u32, ParseHexfError> {
+    let (negative, characteristic, fraction) = parse_hfp(s, allow_underscore, 6)?;
+    Ok(((negative as u128) << 31 | characteristic << 24 | fraction) as u32)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IBM HFP long format.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This is same to `parse_hexf_ibm32` but with 14 hex digits in the fraction,
+/// so 53 to 56 significant bits are available depending on the leading digit.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(parse_hexf_ibm64("0x1.9999999999999p-4", false), Ok(0x4019_9999_9999_9999));
+/// ```
+pub fn parse_hexf_ibm64(s: &str, allow_underscore: bool) -> Result<u64, ParseHexfError> {
+    let (negative, characteristic, fraction) = parse_hfp(s, allow_underscore, 14)?;
+    Ok(((negative as u128) << 63 | characteristic << 56 | fraction) as u64)
+}
+
+/// Tries to parse a hexadecimal float literal to the bits of IBM HFP extended format.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// The extended format consists of two long format numbers,
+/// where the high-order part (the upper 64 bits of the result) has the sign, the characteristic
+/// and the leading 14 hex digits of the 28-digit fraction, and the low-order part has
+/// the remaining 14 digits. As generated by the hardware, the low-order part has the same sign
+/// and the characteristic 14 less than the high-order part (modulo 128) unless the number is zero.
+/// Otherwise this is same to `parse_hexf_ibm32` with 109 to 112 significant bits available.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(
+///     parse_hexf_ibm128("0x1p0", false),
+///     Ok(0x4110_0000_0000_0000_3300_0000_0000_0000)
+/// );
+/// ```
+pub fn parse_hexf_ibm128(s: &str, allow_underscore: bool) -> Result<u128, ParseHexfError> {
+    let (negative, characteristic, fraction) = parse_hfp(s, allow_underscore, 28)?;
+    if characteristic == 0 && fraction == 0 {
+        return Ok((negative as u128) << 127);
+    }
+    let sign = (negative as u128) << 63;
+    let low_characteristic = characteristic.wrapping_sub(14) & 0x7f;
+    let high = sign | characteristic << 56 | fraction >> 56;
+    let low = sign | low_characteristic << 56 | (fraction & ((1 << 56) - 1));
+    Ok(high << 64 | low)
+}
+
+#[test]
+fn test_parse_hexf_ibm32() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf_ibm32(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf_ibm32("0x0p0", false), Ok(0x0000_0000));
+    assert_eq!(parse_hexf_ibm32("-0x0p0", false), Ok(0x8000_0000));
+    assert_eq!(parse_hexf_ibm32("0x1p0", false), Ok(0x4110_0000));
+    assert_eq!(parse_hexf_ibm32("0x1p-1", false), Ok(0x4080_0000));
+    assert_eq!(parse_hexf_ibm32("0x1p4", false), Ok(0x4210_0000));
+    assert_eq!(parse_hexf_ibm32("-0x76.ap0", false), Ok(0xc276_a000));
+    assert_eq!(parse_hexf_ibm32("0x1.9999p-4", false), Ok(0x4019_9990));
+    assert_eq!(parse_hexf_ibm32("0x0.ffffffp252", false), Ok(0x7fff_ffff));
+    assert_eq!(parse_hexf_ibm32("-0x1p-260", false), Ok(0x8010_0000));
+    assert_eq!(parse_hexf_ibm32("0x1_0p-4", true), Ok(0x4110_0000));
+
+    // the precision wobble
+    assert_eq!(parse_hexf_ibm32("0x1.00001p0", false), Ok(0x4110_0001));
+    assert_eq!(parse_hexf_ibm32("0xf.00001p0", false), Ok(0x41f0_0001));
+    assert_eq!(kind("0x1.000008p0"), Inexact);
+    assert_eq!(kind("0x1f.00001p0"), Inexact);
+
+    assert_eq!(kind("0x1p252"), Overflow);
+    assert_eq!(kind("0x1p-261"), Underflow);
+    assert_eq!(kind("inf"), UnsupportedSpecial);
+    assert_eq!(kind("-nan"), UnsupportedSpecial);
+    assert_eq!(kind("nan:0x1"), UnsupportedSpecial);
+    assert_eq!(kind("nan:0xffffffff"), UnsupportedSpecial);
+    assert_eq!(kind("snan:0x0"), UnsupportedSpecial);
+    assert_eq!(kind("-snan:0x1"), UnsupportedSpecial);
+    assert_eq!(kind("inf:0x1"), UnsupportedSpecial);
+
+    let err = parse_hexf_ibm32("0x1.000008p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(9));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 21 significant bits fit, bits from the digit at byte 9 are lost)"
+    );
+    let err = parse_hexf_ibm32("0x8.000008p0", false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 24 significant bits fit, bits from the digit at byte 9 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexf_ibm64() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf_ibm64(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf_ibm64("0x1p0", false), Ok(0x4110_0000_0000_0000));
+    assert_eq!(parse_hexf_ibm64("-0x0p0", false), Ok(0x8000_0000_0000_0000));
+    assert_eq!(
+        parse_hexf_ibm64("-0x76.ap0", false),
+        Ok(0xc276_a000_0000_0000)
+    );
+    assert_eq!(
+        parse_hexf_ibm64("0x0.ffff_ffff_ffff_ffp252", true),
+        Ok(0x7fff_ffff_ffff_ffff)
+    );
+    assert_eq!(
+        parse_hexf_ibm64("0x1.0000000000001p0", false),
+        Ok(0x4110_0000_0000_0001)
+    );
+    assert_eq!(kind("0x1.00000000000008p0"), Inexact);
+    assert_eq!(kind("0x1p252"), Overflow);
+    assert_eq!(kind("0x1p-261"), Underflow);
+}
+
+#[test]
+fn test_parse_hexf_ibm128() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf_ibm128(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf_ibm128("0x0p0", false), Ok(0));
+    assert_eq!(parse_hexf_ibm128("-0x0p0", false), Ok(1 << 127));
+    assert_eq!(
+        parse_hexf_ibm128("-0x1p0", false),
+        Ok(0xc110_0000_0000_0000_b300_0000_0000_0000)
+    );
+    assert_eq!(
+        parse_hexf_ibm128("0x1.0000000000000_00000000000001p0", true),
+        Ok(0x4110_0000_0000_0000_3300_0000_0000_0001)
+    );
+    // the low-order characteristic wraps around
+    assert_eq!(
+        parse_hexf_ibm128("0x1p-256", false),
+        Ok(0x0110_0000_0000_0000_7300_0000_0000_0000)
+    );
+    assert_eq!(
+        parse_hexf_ibm128("0x0.ffffffffffffff_ffffffffffffffp252", true),
+        Ok(0x7fff_ffff_ffff_ffff_71ff_ffff_ffff_ffff)
+    );
+    assert_eq!(kind("0x1.0000000000000000000000000008p0"), Inexact);
+    assert_eq!(kind("0x1p252"), Overflow);
+    assert_eq!(kind("0x1p-261"), Underflow);
+}
@@ -15,47 +15,452 @@
 //! assert_eq!(parse_hexf64("0x0.1_7p8", true), Ok(23.0f64));
 //! ```
 //!
-//! The error is reported via an opaque `ParseHexfError` type.
+//! The error is reported via a `ParseHexfError` type,
+//! whose `kind` method returns the exact cause as a `ParseHexfErrorKind`.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf32("1.5", false).unwrap_err().kind(), ParseHexfErrorKind::MissingPrefix);
+//! assert_eq!(parse_hexf32("0x1p128", false).unwrap_err().kind(), ParseHexfErrorKind::Overflow);
+//! ```
+//!
+//! Syntax errors also record the byte offset of the offending character
+//! and a short description of what was expected there.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! let err = parse_hexf64("0x1.8q", false).unwrap_err();
+//! assert_eq!(err.offset(), Some(5));
+//! assert_eq!(err.expected(), Some("`p` exponent marker"));
+//! ```
+//!
+//! The functions above require the literal to be exactly representable in the target type.
+//! If this is not desirable, `parse_hexf32_rounded` and `parse_hexf64_rounded` accept
+//! a `RoundingMode` and return the correctly rounded value instead.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert!(parse_hexf32("0x1.99999999p-4", false).is_err());
+//! assert_eq!(
+//!     parse_hexf32_rounded("0x1.99999999p-4", false, RoundingMode::NearestTiesEven),
+//!     Ok(0.1f32)
+//! );
+//! assert_eq!(
+//!     parse_hexf32_rounded("0x1.0p128", false, RoundingMode::TowardZero),
+//!     Ok(f32::MAX)
+//! );
+//! ```
+//!
+//! `parse_hexf32_const` and `parse_hexf64_const` are same to `parse_hexf32` and `parse_hexf64`
+//! but are `const fn`s, so they can be used to define constants without procedural macros.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! const C: f64 = match parse_hexf64_const("0x1.8p3", false) {
+//!     Ok(v) => v,
+//!     Err(_) => panic!("invalid hexadecimal float literal"),
+//! };
+//! assert_eq!(C, 12.0);
+//! ```
+//!
+//! Infinities and NaNs can be written as `inf`, `infinity` and `nan`
+//! (case-insensitive, optionally signed).
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf64("-Infinity", false), Ok(f64::NEG_INFINITY));
+//! assert!(parse_hexf32("NaN", false).unwrap().is_nan());
+//! ```
+//!
+//! NaNs can also have an explicit payload, which should fit in the fraction field
+//! without the quiet bit. `nan` always sets the quiet bit and `snan` always clears it.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf64("nan:0x1", false).map(f64::to_bits), Ok(0x7ff8_0000_0000_0001));
+//! assert_eq!(parse_hexf64("-snan:0x1", false).map(f64::to_bits), Ok(0xfff0_0000_0000_0001));
+//! assert!(parse_hexf64("snan:0x0", false).is_err());
+//! ```
+//!
+//! IEEE 754 binary16 (half precision) is also supported by `parse_hexf16`
+//! and `parse_hexf16_rounded`, which return the bits as `u16`.
+//! Enabling the `half` feature adds `parse_hexf16_half` returning `half::f16`,
+//! and enabling the `f16` feature (nightly only) adds `parse_hexf16_native`
+//! returning the primitive `f16` type.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf16("0x1.554p-2", false), Ok(0x3555));
+//! assert!(parse_hexf16("0x1.5555p-2", false).is_err());
+//! assert_eq!(parse_hexf16_rounded("0x1.5555p-2", false, RoundingMode::NearestTiesEven), Ok(0x3555));
+//! ```
+//!
+//! Similarly bfloat16 is supported by `parse_hexbf16` and `parse_hexbf16_rounded`,
+//! and also by `parse_hexbf16_half` returning `half::bf16` with the `half` feature.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexbf16("0x1.98p-4", false), Ok(0x3dcc));
+//! assert_eq!(parse_hexbf16_rounded("0x1.99999ap-4", false, RoundingMode::NearestTiesEven), Ok(0x3dcd));
+//! ```
+//!
+//! IEEE 754 binary128 (quadruple precision) is supported by `parse_hexf128`
+//! and `parse_hexf128_rounded`, which return the bits as `u128`.
+//! Enabling the `f128` feature (nightly only) adds `parse_hexf128_native`
+//! returning the primitive `f128` type.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf128("-0x1.8p1", false), Ok(0xc000_8000_0000_0000_0000_0000_0000_0000));
+//! ```
+//!
+//! The x87 80-bit extended precision format is supported by `parse_hexf80`
+//! and `parse_hexf80_rounded`, which return 10 bytes in the little endian order
+//! with an explicit integer bit, and by `format_hexf80` in the inverse direction.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! let one = [0, 0, 0, 0, 0, 0, 0, 0x80, 0xff, 0x3f];
+//! assert_eq!(parse_hexf80("0x1p0", false), Ok(one));
+//! # #[cfg(feature = "alloc")]
+//! assert_eq!(format_hexf80(one), "0x8.000000000000000p-3");
+//! ```
+//!
+//! Literals with more significant bits can be parsed into double-doubles,
+//! i.e. unevaluated sums `hi + lo` of two floats, with `parse_hexf64x2` and `parse_hexf32x2`.
+//! The literal should be exactly representable as such a sum.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! let (hi, lo) = parse_hexf64x2("0x1.0000000000000_8p0", true).unwrap();
+//! assert_eq!((hi, lo), (1.0, f64::EPSILON / 2.0));
+//! ```
+//!
+//! Other binary formats can be described with `BinaryFormat`,
+//! and `parse_hexf_as` and `parse_hexf_as_rounded` parse literals to their bits.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf_as("-0x1p-126", false, &BinaryFormat::TF32), Ok(0x4_0400));
+//! ```
+//!
+//! IBM hexadecimal floating point (HFP) short, long and extended formats are supported by
+//! `parse_hexf_ibm32`, `parse_hexf_ibm64` and `parse_hexf_ibm128`, which normalize the number
+//! to the base-16 exponent, and by `format_hexf_ibm32` and so on in the inverse direction.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf_ibm32("-0x76.ap0", false), Ok(0xc276_a000));
+//! # #[cfg(feature = "alloc")]
+//! assert_eq!(format_hexf_ibm32(0xc276_a000), "-0x0.76a000p+8");
+//! ```
+//!
+//! OCP FP8 formats have dedicated functions `parse_hexf8e4m3` and `parse_hexf8e5m2`.
+//! E4M3 has no infinities and a single NaN per sign, which extends its largest finite value.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(parse_hexf8e4m3("0x1.cp8", false), Ok(0x7e));
+//! assert_eq!(parse_hexf8e5m2("0x1.cp15", false), Ok(0x7b));
+//! assert!(parse_hexf8e4m3("inf", false).is_err());
+//! ```
+//!
+//! The exact value of a literal can be inspected without any conversion by `parse_hexf_parts`,
+//! which returns its sign, integral significand and binary exponent as a `HexFloatParts`.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! let parts = parse_hexf_parts("-0x1.8p99999", false).unwrap();
+//! assert_eq!(
+//!     parts.normalized(),
+//!     Some(HexFloatParts { negative: true, significand: 3, exponent: 99998 })
+//! );
+//! ```
+//!
+//! With the `alloc` feature (enabled by `std`), `parse_hexf_value` parses any finite literal
+//! into `HexfValue` without losing any bit, which supports exact arithmetic
+//! and can be rounded to floats later.
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use hexf_parse::*;
+//! let x = parse_hexf_value("0x1.0000000000000_0000000000000000_8p0", true).unwrap();
+//! let y = &x * &x;
+//! assert!(y > x);
+//! assert_eq!(y.to_f64_rounded(RoundingMode::TowardPositive), 1.0 + f64::EPSILON);
+//! # }
+//! ```
+//!
+//! The exact decimal expansion of a literal can be written by `parse_hexf_decimal`,
+//! or `ExactDecimal::from_f32` and `ExactDecimal::from_f64` for floats.
+//! The resulting `ExactDecimal` implements `Display` and doesn't allocate,
+//! so it can be written to any `core::fmt::Write` without the `std` feature.
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! let d = parse_hexf_decimal("0x1.999999999999ap-4", false).unwrap();
+//! assert_eq!(d, ExactDecimal::from_f64(0.1).unwrap());
+//! # #[cfg(feature = "std")]
+//! assert_eq!(d.to_string(), "0.1000000000000000055511151231257827021181583404541015625");
+//! ```
+//!
+//! The inverse direction is also provided by `format_hexf32` and `format_hexf64`,
+//! and also by `write_hexf32` and `write_hexf64` which write to any `core::fmt::Write`
+//! (the former requires the `alloc` feature, which is enabled by `std`).
+//!
+//! ```rust
+//! use hexf_parse::*;
+//! assert_eq!(format_hexf32(0.1f32), "0x1.99999ap-4");
+//! assert_eq!(format_hexf64(-0.1f64), "-0x1.999999999999ap-4");
+//! assert_eq!(parse_hexf64(&format_hexf64(-0.1f64), false), Ok(-0.1f64));
+//! ```
+//!
+//! `format_hexf32_shortest` and `format_hexf64_shortest` (and corresponding `write_*` functions)
+//! instead write the fewest digits needed, normalizing subnormal numbers as well,
+//! which still read back to the same bits.
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use hexf_parse::*;
+//! assert_eq!(format_hexf64_shortest(1.0f64), "0x1p+0");
+//! assert_eq!(format_hexf64_shortest(f64::from_bits(1)), "0x1p-1074");
+//! # }
+//! ```
+//!
+//! `format_hexf32_precision` and `format_hexf64_precision` write a fixed number of digits
+//! as C's `%.<precision>a` does, rounding with given `RoundingMode` and matching glibc's output.
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use hexf_parse::*;
+//! assert_eq!(format_hexf64_precision(0.1, 3, RoundingMode::NearestTiesEven), "0x1.99ap-4");
+//! assert_eq!(format_hexf64_precision(1.99, 0, RoundingMode::NearestTiesEven), "0x2p+0");
+//! # }
+//! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
-use core::{f32, f64, fmt, isize, str};
+#![cfg_attr(feature = "f16", feature(f16))]
+#![cfg_attr(feature = "f128", feature(f128))]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::{fmt, str};
+
+mod bfloat16;
+mod binary128;
+mod binary16;
+mod binary_format;
+mod decimal;
+mod double_double;
+mod format;
+mod fp8;
+mod ibm;
+mod parts;
+#[cfg(feature = "alloc")]
+mod value;
+mod x87;
+
+#[cfg(feature = "half")]
+pub use crate::bfloat16::parse_hexbf16_half;
+pub use crate::bfloat16::{parse_hexbf16, parse_hexbf16_rounded};
+#[cfg(feature = "f128")]
+pub use crate::binary128::parse_hexf128_native;
+pub use crate::binary128::{parse_hexf128, parse_hexf128_rounded};
+#[cfg(feature = "half")]
+pub use crate::binary16::parse_hexf16_half;
+#[cfg(feature = "f16")]
+pub use crate::binary16::parse_hexf16_native;
+pub use crate::binary16::{parse_hexf16, parse_hexf16_rounded};
+pub use crate::binary_format::{parse_hexf_as, parse_hexf_as_rounded, BinaryFormat};
+pub use crate::decimal::{parse_hexf_decimal, ExactDecimal};
+pub use crate::double_double::{parse_hexf32x2, parse_hexf64x2};
+#[cfg(feature = "alloc")]
+pub use crate::format::{
+    format_hexbf16, format_hexf128, format_hexf16, format_hexf32, format_hexf32_precision,
+    format_hexf32_shortest, format_hexf64, format_hexf64_precision, format_hexf64_shortest,
+    format_hexf80, format_hexf80_precision, format_hexf8e4m3, format_hexf8e5m2, format_hexf_ibm128,
+    format_hexf_ibm32, format_hexf_ibm64,
+};
+pub use crate::format::{
+    write_hexbf16, write_hexf128, write_hexf16, write_hexf32, write_hexf32_precision,
+    write_hexf32_shortest, write_hexf64, write_hexf64_precision, write_hexf64_shortest,
+    write_hexf80, write_hexf80_precision, write_hexf8e4m3, write_hexf8e5m2, write_hexf_ibm128,
+    write_hexf_ibm32, write_hexf_ibm64,
+};
+pub use crate::fp8::{
+    parse_hexf8e4m3, parse_hexf8e4m3_rounded, parse_hexf8e5m2, parse_hexf8e5m2_rounded,
+};
+pub use crate::ibm::{parse_hexf_ibm128, parse_hexf_ibm32, parse_hexf_ibm64};
+pub use crate::parts::{parse_hexf_parts, HexFloatParts};
+#[cfg(feature = "alloc")]
+pub use crate::value::{parse_hexf_value, HexfValue};
+pub use crate::x87::{parse_hexf80, parse_hexf80_rounded};
 
-/// An opaque error type from `parse_hexf32` and `parse_hexf64`.
+/// An error type from `parse_hexf32` and `parse_hexf64`.
+///
+/// The exact cause of the error can be inspected with the `kind` method.
+/// Syntax errors also record the position of the offending character
+/// and what was expected there, available via the `offset` and `expected` methods.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseHexfError {
     kind: ParseHexfErrorKind,
+    offset: Option<usize>,
+    expected: Option<&'static str>,
+    // for inexact numbers, the number of significant bits available in the target type
+    // and the binary exponent of the least significant bit that can be kept
+    lost: Option<(u32, isize)>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum ParseHexfErrorKind {
+/// The kind of `ParseHexfError`.
+///
+/// More kinds can be added in the future, so matching against this should have a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseHexfErrorKind {
+    /// The input is empty.
     Empty,
-    Invalid,
+    /// The `0x` or `0X` prefix is missing.
+    MissingPrefix,
+    /// There are no hexadecimal digits in the mantissa or no decimal digits in the exponent.
+    MissingDigits,
+    /// The `p` or `P` exponent marker is missing.
+    MissingExponent,
+    /// An underscore is found where it's not allowed.
+    /// This is also reported for any underscore when underscores are not allowed at all.
+    MisplacedUnderscore,
+    /// There are unexpected characters after the exponent.
+    TrailingCharacters,
+    /// The number is too large to be represented in the target type.
+    Overflow,
+    /// The number is non-zero but smaller than the smallest subnormal number of the target type.
+    Underflow,
+    /// The number is in the range of the target type
+    /// but has more significant bits than the target type can represent.
     Inexact,
+    /// The NaN payload does not fit in the fraction field of the target type.
+    /// For signaling NaNs the payload also cannot have the quiet bit set.
+    NanPayloadOverflow,
+    /// The signaling NaN has no payload or a zero payload,
+    /// which would be an infinity instead.
+    ZeroSignalingNan,
+    /// The infinity or NaN is not supported by the target type.
+    UnsupportedSpecial,
+    /// The user-defined `BinaryFormat` is not supported.
+    UnsupportedFormat,
 }
 
 const EMPTY: ParseHexfError = ParseHexfError {
     kind: ParseHexfErrorKind::Empty,
+    offset: Some(0),
+    expected: None,
+    lost: None,
 };
-const INVALID: ParseHexfError = ParseHexfError {
-    kind: ParseHexfErrorKind::Invalid,
+const OVERFLOW: ParseHexfError = ParseHexfError {
+    kind: ParseHexfErrorKind::Overflow,
+    offset: None,
+    expected: None,
+    lost: None,
+};
+const UNDERFLOW: ParseHexfError = ParseHexfError {
+    kind: ParseHexfErrorKind::Underflow,
+    offset: None,
+    expected: None,
+    lost: None,
 };
 const INEXACT: ParseHexfError = ParseHexfError {
     kind: ParseHexfErrorKind::Inexact,
+    offset: None,
+    expected: None,
+    lost: None,
+};
+const UNSUPPORTED_SPECIAL: ParseHexfError = ParseHexfError {
+    kind: ParseHexfErrorKind::UnsupportedSpecial,
+    offset: None,
+    expected: None,
+    lost: None,
 };
 
+// descriptions of what was expected at the position of syntax errors
+const EXPECTED_PREFIX: &str = "`0x` prefix";
+const EXPECTED_HEX_DIGIT: &str = "hex digit";
+const EXPECTED_EXPONENT_MARKER: &str = "`p` exponent marker";
+const EXPECTED_EXPONENT_DIGIT: &str = "decimal exponent digit";
+const EXPECTED_END: &str = "end of input";
+
 impl ParseHexfError {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ParseHexfErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset of the offending character in the input,
+    /// or the length of the input if it ended prematurely.
+    ///
+    /// This is available for syntax errors and empty inputs.
+    /// For inexact numbers this is also available and points to the first hex digit
+    /// that has any bit which cannot be represented in the target type.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns a short description of what was expected at `offset`,
+    /// e.g. `"hex digit"` or `"decimal exponent digit"`.
+    ///
+    /// This is only available for syntax errors.
+    pub fn expected(&self) -> Option<&'static str> {
+        self.expected
+    }
+
     fn text(&self) -> &'static str {
         match self.kind {
             ParseHexfErrorKind::Empty => "cannot parse float from empty string",
-            ParseHexfErrorKind::Invalid => "invalid hexadecimal float literal",
+            ParseHexfErrorKind::MissingPrefix => {
+                "invalid hexadecimal float literal: missing `0x` prefix"
+            }
+            ParseHexfErrorKind::MissingDigits => {
+                "invalid hexadecimal float literal: missing digits"
+            }
+            ParseHexfErrorKind::MissingExponent => {
+                "invalid hexadecimal float literal: missing `p` exponent"
+            }
+            ParseHexfErrorKind::MisplacedUnderscore => {
+                "invalid hexadecimal float literal: misplaced underscore"
+            }
+            ParseHexfErrorKind::TrailingCharacters => {
+                "invalid hexadecimal float literal: unexpected trailing characters"
+            }
+            ParseHexfErrorKind::Overflow => "cannot represent float in target type: too large",
+            ParseHexfErrorKind::Underflow => "cannot represent float in target type: too small",
             ParseHexfErrorKind::Inexact => "cannot exactly represent float in target type",
+            ParseHexfErrorKind::NanPayloadOverflow => {
+                "cannot represent NaN payload in target type: too large"
+            }
+            ParseHexfErrorKind::ZeroSignalingNan => {
+                "cannot represent signaling NaN with zero payload"
+            }
+            ParseHexfErrorKind::UnsupportedSpecial => {
+                "cannot represent infinity or NaN in target type: not supported"
+            }
+            ParseHexfErrorKind::UnsupportedFormat => "cannot parse float into unsupported format",
         }
     }
 }
 
 impl fmt::Display for ParseHexfError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(self.text(), f)
+        fmt::Display::fmt(self.text(), f)?;
+        if let (Some(offset), Some(expected)) = (self.offset, self.expected) {
+            write!(f, " (expected {} at byte {})", expected, offset)?;
+        }
+        if let Some((precision, _)) = self.lost {
+            let plural = if precision == 1 { "" } else { "s" };
+            write!(f, " (only {} significant bit{} fit", precision, plural)?;
+            if let Some(offset) = self.offset {
+                write!(f, ", bits from the digit at byte {} are lost", offset)?;
+            }
+            f.write_str(")")?;
+        }
+        Ok(())
     }
 }
 
@@ -73,7 +478,129 @@ impl core::error::Error for ParseHexfError {
     }
 }
 
-fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHexfError> {
+/// A rounding mode for `parse_hexf32_rounded` and `parse_hexf64_rounded`.
+///
+/// Overflowing values are rounded to either an infinity or the largest finite value,
+/// and values below the smallest subnormal number are rounded to either zero or
+/// the smallest subnormal number, as dictated by each mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Rounds to the nearest representable value, and to the even one on ties.
+    /// Overflows to an infinity.
+    NearestTiesEven,
+    /// Rounds to the nearest representable value, and away from zero on ties.
+    /// Overflows to an infinity.
+    NearestTiesAway,
+    /// Rounds toward zero (i.e. truncates).
+    /// Overflows to the largest finite value.
+    TowardZero,
+    /// Rounds toward the positive infinity.
+    TowardPositive,
+    /// Rounds toward the negative infinity.
+    TowardNegative,
+    /// Truncates and then sets the least significant bit if any bit was lost.
+    /// Overflows to the largest finite value.
+    ///
+    /// This is useful when the result is going to be rounded again to a narrower type,
+    /// as it avoids the double rounding error.
+    ToOdd,
+}
+
+impl RoundingMode {
+    // decides whether the truncated mantissa should be incremented, given its least
+    // significant bit (`odd`), the first discarded bit (`half`) and whether any other
+    // discarded bit is set (`sticky`).
+    const fn round_up(self, negative: bool, odd: bool, half: bool, sticky: bool) -> bool {
+        let inexact = half || sticky;
+        match self {
+            RoundingMode::NearestTiesEven => half && (sticky || odd),
+            RoundingMode::NearestTiesAway => half,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => inexact && !negative,
+            RoundingMode::TowardNegative => inexact && negative,
+            // incrementing an even mantissa is same to setting the least significant bit
+            RoundingMode::ToOdd => inexact && !odd,
+        }
+    }
+
+    // decides whether an overflowing value becomes an infinity or the largest finite value.
+    const fn overflows_to_infinity(self, negative: bool) -> bool {
+        match self {
+            RoundingMode::NearestTiesEven | RoundingMode::NearestTiesAway => true,
+            RoundingMode::TowardZero | RoundingMode::ToOdd => false,
+            RoundingMode::TowardPositive => !negative,
+            RoundingMode::TowardNegative => negative,
+        }
+    }
+}
+
+// returns a syntax error for an unexpected character at the start of `rest`,
+// which is a suffix of `input`. an underscore there is always reported as misplaced.
+const fn unexpected(
+    input: &[u8],
+    rest: &[u8],
+    kind: ParseHexfErrorKind,
+    expected: &'static str,
+) -> ParseHexfError {
+    let kind = if let Some(&b'_') = rest.first() {
+        ParseHexfErrorKind::MisplacedUnderscore
+    } else {
+        kind
+    };
+    ParseHexfError {
+        kind,
+        offset: Some(input.len() - rest.len()),
+        expected: Some(expected),
+        lost: None,
+    }
+}
+
+// `?` is not yet available in const fns, so this returns `INEXACT` for `None` instead.
+// inexactness is the only error that can result from overflowing digit counts.
+macro_rules! try_inexact {
+    ($e:expr) => {
+        match $e {
+            Some(v) => v,
+            None => return Err(INEXACT),
+        }
+    };
+}
+
+// parses a literal into `(negative, mantissa, exponent)` for `mantissa * 2^exponent`,
+// where any bits that didn't fit in the mantissa are summarized in its least significant bit.
+const fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u128, isize), ParseHexfError> {
+    match parse_truncated(s, allow_underscore) {
+        // the accumulator has at least 125 significant bits when the sticky flag is set,
+        // so setting its least significant bit is enough for the correct rounding
+        Ok((negative, acc, exponent, true)) => Ok((negative, acc | 1, saturate_exponent(exponent))),
+        Ok((negative, acc, exponent, false)) => Ok((negative, acc, saturate_exponent(exponent))),
+        Err(err) => Err(err),
+    }
+}
+
+// clamps an exponent returned by `parse_truncated` to `isize`,
+// which is still way out of range for any supported type when it actually clamps.
+const fn saturate_exponent(exponent: i128) -> isize {
+    if exponent > isize::MAX as i128 {
+        isize::MAX
+    } else if exponent < isize::MIN as i128 {
+        isize::MIN
+    } else {
+        exponent as isize
+    }
+}
+
+// same to `parse` but returns the truncated mantissa and the sticky flag separately,
+// which is set when any non-zero bit below the mantissa has been dropped.
+// the exponent is returned as `i128` so that callers can tell whether it fits in `isize`;
+// it only saturates when the exponent itself has more than 38 decimal digits.
+const fn parse_truncated(
+    s: &[u8],
+    allow_underscore: bool,
+) -> Result<(bool, u128, i128, bool), ParseHexfError> {
+    use ParseHexfErrorKind::*;
+    let input = s;
+
     // ^[+-]?
     let (s, negative) = match s.split_first() {
         Some((&b'+', s)) => (s, false),
@@ -83,13 +610,18 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
     };
 
     // 0[xX]
-    if !(s.starts_with(b"0x") || s.starts_with(b"0X")) {
-        return Err(INVALID);
-    }
+    let mut s = match s {
+        [b'0', b'x', s @ ..] | [b'0', b'X', s @ ..] => s,
+        _ => return Err(unexpected(input, s, MissingPrefix, EXPECTED_PREFIX)),
+    };
 
     // ([0-9a-fA-F][0-9a-fA-F_]*)?
-    let mut s = &s[2..];
-    let mut acc = 0; // the accumulated mantissa
+    // the accumulator only keeps the most significant 125 to 128 bits of the mantissa,
+    // which is more than enough to round to any supported type.
+    // every other non-zero bit is recorded in the sticky flag, and only counted for the exponent.
+    let mut acc = 0u128; // the accumulated mantissa
+    let mut sticky = false; // true if any non-zero digit didn't fit in `acc`
+    let mut nextras = 0isize; // the number of integral digits that didn't fit in `acc`
     let mut digit_seen = false;
     loop {
         let (s_, digit) = match s.split_first() {
@@ -106,11 +638,13 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
         s = s_;
         digit_seen = true;
 
-        // if `acc << 4` fails, mantissa definitely exceeds 64 bits so we should bail out
-        if acc >> 60 != 0 {
-            return Err(INEXACT);
+        // if `acc << 4` fails, the digit only contributes to the sticky flag and the exponent
+        if acc >> 124 != 0 {
+            sticky |= digit != 0;
+            nextras = nextras.saturating_add(1);
+        } else {
+            acc = acc << 4 | digit as u128;
         }
-        acc = acc << 4 | digit as u64;
     }
 
     // (\.[0-9a-fA-F][0-9a-fA-F_]*)?
@@ -119,8 +653,8 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
     let mut nfracs = 0isize; // this is suboptimal but also practical, see below
     let mut nzeroes = 0isize;
     let mut frac_digit_seen = false;
-    if s.starts_with(b".") {
-        s = &s[1..];
+    if let [b'.', s_ @ ..] = s {
+        s = s_;
         loop {
             let (s_, digit) = match s.split_first() {
                 Some((&c @ b'0'..=b'9', s)) => (s, c - b'0'),
@@ -137,36 +671,52 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
             frac_digit_seen = true;
 
             if digit == 0 {
-                nzeroes = nzeroes.checked_add(1).ok_or(INEXACT)?;
+                nzeroes = try_inexact!(nzeroes.checked_add(1));
             } else {
                 // flush nzeroes
-                let nnewdigits = nzeroes.checked_add(1).ok_or(INEXACT)?;
-                nfracs = nfracs.checked_add(nnewdigits).ok_or(INEXACT)?;
+                let nnewdigits = try_inexact!(nzeroes.checked_add(1));
                 nzeroes = 0;
 
-                // if the accumulator is non-zero, the shift cannot exceed 64
-                // (therefore the number of new digits cannot exceed 16).
-                // this will catch e.g. `0.40000....00001` with sufficiently many zeroes
-                if acc != 0 {
-                    if nnewdigits >= 16 || acc >> (64 - nnewdigits * 4) != 0 {
-                        return Err(INEXACT);
+                // if the accumulator is zero, leading zeroes only affect the exponent.
+                // otherwise shift in as many digits as possible, and if the new non-zero digit
+                // doesn't fit (e.g. `0.40000....00001` with sufficiently many zeroes)
+                // fill the accumulator with zeroes and record the digit in the sticky flag.
+                let room = if acc == 0 {
+                    nnewdigits
+                } else {
+                    (acc.leading_zeros() / 4) as isize
+                };
+                if nnewdigits <= room {
+                    nfracs = try_inexact!(nfracs.checked_add(nnewdigits));
+                    if acc != 0 {
+                        acc <<= nnewdigits * 4;
                     }
-                    acc = acc << (nnewdigits * 4);
+                    acc |= digit as u128;
+                } else {
+                    nfracs = try_inexact!(nfracs.checked_add(room));
+                    acc <<= room * 4;
+                    sticky = true;
                 }
-                acc |= digit as u64;
             }
         }
     }
 
     // at least one digit should be present
     if !(digit_seen || frac_digit_seen) {
-        return Err(INVALID);
+        return Err(unexpected(input, s, MissingDigits, EXPECTED_HEX_DIGIT));
     }
 
     // [pP]
     let s = match s.split_first() {
         Some((&b'P', s)) | Some((&b'p', s)) => s,
-        _ => return Err(INVALID),
+        _ => {
+            return Err(unexpected(
+                input,
+                s,
+                MissingExponent,
+                EXPECTED_EXPONENT_MARKER,
+            ))
+        }
     };
 
     // [+-]?
@@ -174,12 +724,12 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
         Some((&b'+', s)) => (s, false),
         Some((&b'-', s)) => (s, true),
         Some(_) => (s, false),
-        None => return Err(INVALID),
+        None => return Err(unexpected(input, s, MissingDigits, EXPECTED_EXPONENT_DIGIT)),
     };
 
     // [0-9_]*[0-9][0-9_]*$
     let mut digit_seen = false;
-    let mut exponent = 0isize; // this is suboptimal but also practical, see below
+    let mut exponent = 0i128;
     loop {
         let (s_, digit) = match s.split_first() {
             Some((&c @ b'0'..=b'9', s)) => (s, c - b'0'),
@@ -189,18 +739,21 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
             }
             None if digit_seen => break,
             // no more bytes expected, and at least one exponent digit should be present
-            _ => return Err(INVALID),
+            _ if digit_seen => {
+                return Err(unexpected(input, s, TrailingCharacters, EXPECTED_END));
+            }
+            _ => {
+                return Err(unexpected(input, s, MissingDigits, EXPECTED_EXPONENT_DIGIT));
+            }
         };
 
         s = s_;
         digit_seen = true;
 
         // if we have no non-zero digits at this point, ignore the exponent :-)
+        // otherwise saturate the exponent, which is already way out of range at that point.
         if acc != 0 {
-            exponent = exponent
-                .checked_mul(10)
-                .and_then(|v| v.checked_add(digit as isize))
-                .ok_or(INEXACT)?;
+            exponent = exponent.saturating_mul(10).saturating_add(digit as i128);
         }
     }
     if negative_exponent {
@@ -209,40 +762,98 @@ fn parse(s: &[u8], allow_underscore: bool) -> Result<(bool, u64, isize), ParseHe
 
     if acc == 0 {
         // ignore the exponent as above
-        Ok((negative, 0, 0))
+        Ok((negative, 0, 0, false))
     } else {
-        // the exponent should be biased by (nfracs * 4) to match with the mantissa read.
+        // the exponent should be biased by (nfracs * 4) to match with the mantissa read,
+        // and also by (nextras * 4) for integral digits that didn't fit.
         // we still miss valid inputs like `0.0000...0001pX` where the input is filling
         // at least 1/4 of the total addressable memory, but I dare not handle them!
-        let exponent = nfracs
-            .checked_mul(4)
-            .and_then(|v| exponent.checked_sub(v))
-            .ok_or(INEXACT)?;
-        Ok((negative, acc, exponent))
+        let exponent = exponent
+            .saturating_add(nextras as i128 * 4)
+            .saturating_sub(nfracs as i128 * 4);
+        Ok((negative, acc, exponent, sticky))
+    }
+}
+
+// a xorshift64 generator for tests, which yields pseudorandom states from given non-zero seed.
+#[cfg(test)]
+struct XorShift64(u64);
+
+#[cfg(test)]
+impl Iterator for XorShift64 {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        Some(self.0)
     }
 }
 
 #[test]
 fn test_parse() {
+    use ParseHexfErrorKind::*;
+    let err = |kind, offset, expected| {
+        Err(ParseHexfError {
+            kind,
+            offset: Some(offset),
+            expected: Some(expected),
+            lost: None,
+        })
+    };
+
     assert_eq!(parse(b"", false), Err(EMPTY));
-    assert_eq!(parse(b" ", false), Err(INVALID));
-    assert_eq!(parse(b"3.14", false), Err(INVALID));
-    assert_eq!(parse(b"0x3.14", false), Err(INVALID));
+    assert_eq!(parse(b" ", false), err(MissingPrefix, 0, EXPECTED_PREFIX));
+    assert_eq!(
+        parse(b"3.14", false),
+        err(MissingPrefix, 0, EXPECTED_PREFIX)
+    );
+    assert_eq!(
+        parse(b"0x3.14", false),
+        err(MissingExponent, 6, EXPECTED_EXPONENT_MARKER)
+    );
     assert_eq!(parse(b"0x3.14fp+3", false), Ok((false, 0x314f, 3 - 12)));
-    assert_eq!(parse(b" 0x3.14p+3", false), Err(INVALID));
-    assert_eq!(parse(b"0x3.14p+3 ", false), Err(INVALID));
+    assert_eq!(
+        parse(b" 0x3.14p+3", false),
+        err(MissingPrefix, 0, EXPECTED_PREFIX)
+    );
+    assert_eq!(
+        parse(b"0x3.14p+3 ", false),
+        err(TrailingCharacters, 9, EXPECTED_END)
+    );
     assert_eq!(parse(b"+0x3.14fp+3", false), Ok((false, 0x314f, 3 - 12)));
     assert_eq!(parse(b"-0x3.14fp+3", false), Ok((true, 0x314f, 3 - 12)));
     assert_eq!(parse(b"0xAbC.p1", false), Ok((false, 0xabc, 1)));
     assert_eq!(parse(b"0x0.7p1", false), Ok((false, 0x7, 1 - 4)));
     assert_eq!(parse(b"0x.dEfP-1", false), Ok((false, 0xdef, -1 - 12)));
-    assert_eq!(parse(b"0x.p1", false), Err(INVALID));
-    assert_eq!(parse(b"0x.P1", false), Err(INVALID));
-    assert_eq!(parse(b"0xp1", false), Err(INVALID));
-    assert_eq!(parse(b"0xP1", false), Err(INVALID));
-    assert_eq!(parse(b"0x0p", false), Err(INVALID));
-    assert_eq!(parse(b"0xp", false), Err(INVALID));
-    assert_eq!(parse(b"0x.p", false), Err(INVALID));
+    assert_eq!(
+        parse(b"0x.p1", false),
+        err(MissingDigits, 3, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x.P1", false),
+        err(MissingDigits, 3, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0xp1", false),
+        err(MissingDigits, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0xP1", false),
+        err(MissingDigits, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x0p", false),
+        err(MissingDigits, 4, EXPECTED_EXPONENT_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0xp", false),
+        err(MissingDigits, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x.p", false),
+        err(MissingDigits, 3, EXPECTED_HEX_DIGIT)
+    );
     assert_eq!(parse(b"0x0p1", false), Ok((false, 0, 0)));
     assert_eq!(parse(b"0x0P1", false), Ok((false, 0, 0)));
     assert_eq!(parse(b"0x0.p1", false), Ok((false, 0, 0)));
@@ -263,11 +874,15 @@ fn test_parse() {
     );
     assert_eq!(
         parse(b"0x1.p99999999999999999999999999999", false),
-        Err(INEXACT)
+        Ok((false, 1, isize::MAX))
     );
     assert_eq!(
         parse(b"0x1.p-99999999999999999999999999999", false),
-        Err(INEXACT)
+        Ok((false, 1, isize::MIN))
+    );
+    assert_eq!(
+        parse(b"0x1.8p-99999999999999999999999999999", false),
+        Ok((false, 0x18, isize::MIN))
     );
     assert_eq!(
         parse(b"0x4.00000000000000000000p55", false),
@@ -277,123 +892,746 @@ fn test_parse() {
         parse(b"0x4.00001000000000000000p55", false),
         Ok((false, 0x400001, 55 - 20))
     );
-    assert_eq!(parse(b"0x4.00000000000000000001p55", false), Err(INEXACT));
-
-    // underscore insertion
     assert_eq!(
-        parse(b"-0x3____.1_4___p+___5___", true),
-        Ok((true, 0x314, 5 - 8))
+        parse(b"0x4.00000000000000000001p55", false),
+        Ok((false, 0x4_0000_0000_0000_0000_0001, 55 - 80))
+    );
+    assert_eq!(
+        parse(b"0x4.000000000000000000000000000000000001p55", false),
+        Ok((false, 0x4000_0000_0000_0000_0000_0000_0000_0001, 55 - 124))
     );
-    assert_eq!(parse(b"-_0x3.14p+5", true), Err(INVALID));
-    assert_eq!(parse(b"_0x3.14p+5", true), Err(INVALID));
-    assert_eq!(parse(b"0x_3.14p+5", true), Err(INVALID));
-    assert_eq!(parse(b"0x3._14p+5", true), Err(INVALID));
-    assert_eq!(parse(b"0x3.14p_+5", true), Err(INVALID));
-    assert_eq!(parse(b"-0x____.1_4___p+___5___", true), Err(INVALID));
-    assert_eq!(parse(b"-0x3____.____p+___5___", true), Err(INVALID));
-    assert_eq!(parse(b"-0x3____.1_4___p+______", true), Err(INVALID));
-    assert_eq!(parse(b"0x_p0", false), Err(INVALID));
-    assert_eq!(parse(b"0x_0p0", true), Err(INVALID));
-    assert_eq!(parse(b"0x_p0", true), Err(INVALID));
-    assert_eq!(parse(b"0x._p0", true), Err(INVALID));
-    assert_eq!(parse(b"0x._0p0", true), Err(INVALID));
-    assert_eq!(parse(b"0x0._0p0", true), Err(INVALID));
-    assert_eq!(parse(b"0x0_p0", true), Ok((false, 0, 0)));
-    assert_eq!(parse(b"0x.0_p0", true), Ok((false, 0, 0)));
-    assert_eq!(parse(b"0x0.0_p0", true), Ok((false, 0, 0)));
-
-    // issues
-    // #11 (https://github.com/lifthrasiir/hexf/issues/11)
-    assert_eq!(parse(b"0x1p-149", false), parse(b"0x1.0p-149", false));
-}
-
-macro_rules! define_convert {
-    ($name:ident => $f:ident) => {
-        fn $name(negative: bool, mantissa: u64, exponent: isize) -> Result<$f, ParseHexfError> {
-            // guard the exponent with the definitely safe range (we will exactly bound it later)
-            if exponent < -0xffff || exponent > 0xffff {
-                return Err(INEXACT);
-            }
-
-            // strip the trailing zeroes in mantissa and adjust exponent.
-            // we do this because a unit in the least significant bit of mantissa is
-            // always safe to represent while one in the most significant bit isn't.
-            let trailing = mantissa.trailing_zeros() & 63; // guard mantissa=0 case
-            let mantissa = mantissa >> trailing;
-            let exponent = exponent + trailing as isize;
-
-            // normalize the exponent that the number is (1.xxxx * 2^normalexp),
-            // and check for the mantissa and exponent ranges
-            let leading = mantissa.leading_zeros();
-            let normalexp = exponent + (63 - leading as isize);
-            let mantissasize = if normalexp < $f::MIN_EXP as isize - $f::MANTISSA_DIGITS as isize {
-                // the number is smaller than the minimal denormal number
-                return Err(INEXACT);
-            } else if normalexp < ($f::MIN_EXP - 1) as isize {
-                // the number is denormal, the # of bits in the mantissa is:
-                // - minimum (1) at MIN_EXP - MANTISSA_DIGITS
-                // - maximum (MANTISSA_DIGITS - 1) at MIN_EXP - 2
-                $f::MANTISSA_DIGITS as isize - $f::MIN_EXP as isize + normalexp + 1
-            } else if normalexp < $f::MAX_EXP as isize {
-                // the number is normal, the # of bits in the mantissa is fixed
-                $f::MANTISSA_DIGITS as isize
-            } else {
-                // the number is larger than the maximal denormal number
-                // ($f::MAX_EXP denotes NaN and infinities here)
-                return Err(INEXACT);
-            };
-
-            if mantissa >> mantissasize == 0 {
-                let mut mantissa = mantissa as $f;
-                if negative {
-                    mantissa = -mantissa;
-                }
-                // yes, powi somehow does not work!
-                Ok(mantissa * libm::exp2(exponent as f64) as $f)
-            } else {
-                Err(INEXACT)
-            }
-        }
-    };
-}
-
-define_convert!(convert_hexf32 => f32);
-define_convert!(convert_hexf64 => f64);
-
-#[test]
-fn test_convert_hexf32() {
-    assert_eq!(convert_hexf32(false, 0, 0), Ok(0.0));
-    assert_eq!(convert_hexf32(false, 1, 0), Ok(1.0));
-    assert_eq!(convert_hexf32(false, 10, 0), Ok(10.0));
-    assert_eq!(convert_hexf32(false, 10, 1), Ok(20.0));
-    assert_eq!(convert_hexf32(false, 10, -1), Ok(5.0));
-    assert_eq!(convert_hexf32(true, 0, 0), Ok(-0.0));
-    assert_eq!(convert_hexf32(true, 1, 0), Ok(-1.0));
-
-    // negative zeroes
-    assert_eq!(convert_hexf32(false, 0, 0).unwrap().signum(), 1.0);
-    assert_eq!(convert_hexf32(true, 0, 0).unwrap().signum(), -1.0);
 
-    // normal truncation
+    // long significands
     assert_eq!(
-        convert_hexf32(false, 0x0000_0000_00ff_ffff, 0),
-        Ok(16777215.0)
+        parse(b"0x10000000000000000000p-80", false),
+        Ok((false, 0x1000_0000_0000_0000_0000, -80))
     );
     assert_eq!(
-        convert_hexf32(false, 0x0000_0000_01ff_ffff, 0),
-        Err(INEXACT)
+        parse(b"0x10000000000000000001p-80", false),
+        Ok((false, 0x1000_0000_0000_0000_0001, -80))
     );
     assert_eq!(
-        convert_hexf32(false, 0xffff_ff00_0000_0000, -40),
-        Ok(16777215.0)
+        parse(b"0x100000000000000000000000000000000000p-144", false),
+        Ok((false, 0x1000_0000_0000_0000_0000_0000_0000_0000, -128))
     );
     assert_eq!(
-        convert_hexf32(false, 0xffff_ff80_0000_0000, -40),
-        Err(INEXACT)
+        parse(b"0x100000000000000000000000000000000001p-144", false),
+        Ok((false, 0x1000_0000_0000_0000_0000_0000_0000_0001, -128))
+    );
+    assert_eq!(
+        parse(b"0x1234567890abcdef1234.5p0", false),
+        Ok((false, 0x1_2345_6789_0abc_def1_2345, -4))
+    );
+    assert_eq!(
+        parse(b"0x1234567890abcdef1234567890abcdef1234.5p0", false),
+        Ok((false, 0x1234_5678_90ab_cdef_1234_5678_90ab_cdef, 16))
+    );
+    assert_eq!(
+        parse(b"0x.00000000000000000000123456789abcdef1p0", false),
+        Ok((false, 0x1234_5678_9abc_def1, -36 * 4))
+    );
+    assert_eq!(
+        parse(b"0x.00000000000000000000123456789abcdef12p0", false),
+        Ok((false, 0x1_2345_6789_abcd_ef12, -37 * 4))
+    );
+    assert_eq!(
+        parse(b"0x.00000000000000000000123456789abcdef1200000000p0", false),
+        Ok((false, 0x1_2345_6789_abcd_ef12, -37 * 4))
+    );
+    assert_eq!(
+        parse(
+            b"0x.00000000000000000000123456789abcdef10000000001p0",
+            false
+        ),
+        Ok((false, 0x12_3456_789a_bcde_f100_0000_0001, -46 * 4))
+    );
+    assert_eq!(
+        parse(
+            b"0x.00000000000000000000123456789abcdef0123456789abcdef000000001p0",
+            false
+        ),
+        Ok((false, 0x1234_5678_9abc_def0_1234_5678_9abc_def1, -52 * 4))
+    );
+    assert_eq!(
+        parse(
+            b"0x.00000000000000000000123456789abcdef0123456789abcdef10000000001p0",
+            false
+        ),
+        Ok((false, 0x1234_5678_9abc_def0_1234_5678_9abc_def1, -52 * 4))
     );
 
-    // denormal truncation
-    assert!(convert_hexf32(false, 0x0000_0000_007f_ffff, -149).is_ok());
+    // underscore insertion
+    assert_eq!(
+        parse(b"-0x3____.1_4___p+___5___", true),
+        Ok((true, 0x314, 5 - 8))
+    );
+    assert_eq!(
+        parse(b"-_0x3.14p+5", true),
+        err(MisplacedUnderscore, 1, EXPECTED_PREFIX)
+    );
+    assert_eq!(
+        parse(b"_0x3.14p+5", true),
+        err(MisplacedUnderscore, 0, EXPECTED_PREFIX)
+    );
+    assert_eq!(
+        parse(b"0x_3.14p+5", true),
+        err(MisplacedUnderscore, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x3._14p+5", true),
+        err(MisplacedUnderscore, 4, EXPECTED_EXPONENT_MARKER)
+    );
+    assert_eq!(
+        parse(b"0x3.14p_+5", true),
+        err(MissingDigits, 8, EXPECTED_EXPONENT_DIGIT)
+    );
+    assert_eq!(
+        parse(b"-0x____.1_4___p+___5___", true),
+        err(MisplacedUnderscore, 3, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"-0x3____.____p+___5___", true),
+        err(MisplacedUnderscore, 9, EXPECTED_EXPONENT_MARKER)
+    );
+    assert_eq!(
+        parse(b"-0x3____.1_4___p+______", true),
+        err(MissingDigits, 23, EXPECTED_EXPONENT_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x_p0", false),
+        err(MisplacedUnderscore, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x_0p0", true),
+        err(MisplacedUnderscore, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x_p0", true),
+        err(MisplacedUnderscore, 2, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x._p0", true),
+        err(MisplacedUnderscore, 3, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x._0p0", true),
+        err(MisplacedUnderscore, 3, EXPECTED_HEX_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x0._0p0", true),
+        err(MisplacedUnderscore, 4, EXPECTED_EXPONENT_MARKER)
+    );
+    assert_eq!(parse(b"0x0_p0", true), Ok((false, 0, 0)));
+    assert_eq!(parse(b"0x.0_p0", true), Ok((false, 0, 0)));
+    assert_eq!(parse(b"0x0.0_p0", true), Ok((false, 0, 0)));
+    assert_eq!(
+        parse(b"0x1_p0", false),
+        err(MisplacedUnderscore, 3, EXPECTED_EXPONENT_MARKER)
+    );
+    assert_eq!(
+        parse(b"0x1p1_", false),
+        err(MisplacedUnderscore, 5, EXPECTED_END)
+    );
+
+    // other error kinds
+    assert_eq!(parse(b"-", false), err(MissingPrefix, 1, EXPECTED_PREFIX));
+    assert_eq!(
+        parse(b"0x1.8z", false),
+        err(MissingExponent, 5, EXPECTED_EXPONENT_MARKER)
+    );
+    assert_eq!(
+        parse(b"0x1p+x", false),
+        err(MissingDigits, 5, EXPECTED_EXPONENT_DIGIT)
+    );
+    assert_eq!(
+        parse(b"0x1p1g", false),
+        err(TrailingCharacters, 5, EXPECTED_END)
+    );
+
+    // issues
+    // #11 (https://github.com/lifthrasiir/hexf/issues/11)
+    assert_eq!(parse(b"0x1p-149", false), parse(b"0x1.0p-149", false));
+}
+
+// a special value accepted in place of a hexadecimal float literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Special {
+    Infinity,
+    // the exact fraction field of the NaN, including the quiet bit
+    Nan(u128),
+}
+
+// same to `<[u8]>::eq_ignore_ascii_case`, which is not `const` until Rust 1.86.
+const fn eq_ignore_case(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if !a[i].eq_ignore_ascii_case(&b[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// splits `[+-]?name(:.*)?` into the sign, the name and the rest starting with `:` if any.
+const fn split_special(s: &[u8]) -> (bool, &[u8], &[u8]) {
+    // ^[+-]?
+    let (s, negative) = match s.split_first() {
+        Some((&b'+', s)) => (s, false),
+        Some((&b'-', s)) => (s, true),
+        _ => (s, false),
+    };
+
+    let mut colon = 0;
+    while colon < s.len() && s[colon] != b':' {
+        colon += 1;
+    }
+    let (name, rest) = s.split_at(colon);
+    (negative, name, rest)
+}
+
+// returns true if the input names a special value, whether or not the rest is valid.
+// this is used to reject special values regardless of their payloads.
+const fn is_special(s: &[u8]) -> bool {
+    let (_, name, _) = split_special(s);
+    eq_ignore_case(name, b"inf")
+        || eq_ignore_case(name, b"infinity")
+        || eq_ignore_case(name, b"nan")
+        || eq_ignore_case(name, b"snan")
+}
+
+// parses `[+-]?(inf|infinity|nan(:0x[0-9a-fA-F]+)?|snan:0x[0-9a-fA-F]+)` case-insensitively,
+// where the target type has `fraction_bits` bits in the fraction field.
+// returns `Ok(None)` if the input is not a special value at all.
+//
+// the payload is the fraction field without the quiet bit, which is set for `nan`
+// (it can be also written in the payload) and should be cleared for `snan`.
+const fn parse_special(
+    s: &[u8],
+    allow_underscore: bool,
+    fraction_bits: u32,
+) -> Result<Option<(bool, Special)>, ParseHexfError> {
+    use ParseHexfErrorKind::*;
+    let input = s;
+
+    let (negative, name, rest) = split_special(s);
+    let signaling = if eq_ignore_case(name, b"inf") || eq_ignore_case(name, b"infinity") {
+        if !rest.is_empty() {
+            return Err(unexpected(input, rest, TrailingCharacters, EXPECTED_END));
+        }
+        return Ok(Some((negative, Special::Infinity)));
+    } else if eq_ignore_case(name, b"nan") {
+        false
+    } else if eq_ignore_case(name, b"snan") {
+        true
+    } else {
+        return Ok(None);
+    };
+
+    // (:0[xX][0-9a-fA-F][0-9a-fA-F_]*)?$
+    let offset = input.len() - rest.len() + 1; // the start of the payload if any
+    let mut payload = 0u128;
+    if let Some((_, s)) = rest.split_first() {
+        let mut s = match s {
+            [b'0', b'x', s @ ..] | [b'0', b'X', s @ ..] => s,
+            _ => return Err(unexpected(input, s, MissingPrefix, EXPECTED_PREFIX)),
+        };
+        let mut digit_seen = false;
+        loop {
+            let (s_, digit) = match s.split_first() {
+                Some((&c @ b'0'..=b'9', s)) => (s, c - b'0'),
+                Some((&c @ b'a'..=b'f', s)) => (s, c - b'a' + 10),
+                Some((&c @ b'A'..=b'F', s)) => (s, c - b'A' + 10),
+                Some((&b'_', s_)) if allow_underscore && digit_seen => {
+                    s = s_;
+                    continue;
+                }
+                None if digit_seen => break,
+                _ if digit_seen => {
+                    return Err(unexpected(input, s, TrailingCharacters, EXPECTED_END));
+                }
+                _ => return Err(unexpected(input, s, MissingDigits, EXPECTED_HEX_DIGIT)),
+            };
+
+            s = s_;
+            digit_seen = true;
+
+            // any payload with more than 128 bits never fits in the fraction field anyway
+            if payload >> 124 != 0 {
+                payload = u128::MAX;
+            } else {
+                payload = payload << 4 | digit as u128;
+            }
+        }
+    }
+
+    let quiet = 1u128 << (fraction_bits - 1);
+    let (limit, fraction) = if signaling {
+        (quiet, payload)
+    } else {
+        (quiet << 1, payload | quiet)
+    };
+    if payload >= limit {
+        return Err(ParseHexfError {
+            kind: NanPayloadOverflow,
+            offset: Some(offset),
+            expected: None,
+            lost: None,
+        });
+    }
+    if fraction == 0 {
+        return Err(ParseHexfError {
+            kind: ZeroSignalingNan,
+            offset: Some(if offset < input.len() {
+                offset
+            } else {
+                input.len()
+            }),
+            expected: None,
+            lost: None,
+        });
+    }
+    Ok(Some((negative, Special::Nan(fraction))))
+}
+
+#[test]
+fn test_parse_special() {
+    use ParseHexfErrorKind::*;
+    let err = |kind, offset, expected| {
+        Err(ParseHexfError {
+            kind,
+            offset: Some(offset),
+            expected,
+            lost: None,
+        })
+    };
+    let parse64 = |s: &[u8]| parse_special(s, false, 52);
+
+    assert_eq!(parse64(b"inf"), Ok(Some((false, Special::Infinity))));
+    assert_eq!(parse64(b"+INF"), Ok(Some((false, Special::Infinity))));
+    assert_eq!(parse64(b"-Infinity"), Ok(Some((true, Special::Infinity))));
+    assert_eq!(parse64(b"NaN"), Ok(Some((false, Special::Nan(1 << 51)))));
+    assert_eq!(parse64(b"-nan"), Ok(Some((true, Special::Nan(1 << 51)))));
+    assert_eq!(parse64(b""), Ok(None));
+    assert_eq!(parse64(b"-"), Ok(None));
+    assert_eq!(parse64(b"infinit"), Ok(None));
+    assert_eq!(parse64(b"in_f"), Ok(None));
+    assert_eq!(parse64(b"+-inf"), Ok(None));
+    assert_eq!(parse64(b"0xinf"), Ok(None));
+    assert_eq!(
+        parse64(b"inf:0x1"),
+        err(TrailingCharacters, 3, Some(EXPECTED_END))
+    );
+
+    // payloads
+    assert_eq!(
+        parse64(b"nan:0x1"),
+        Ok(Some((false, Special::Nan(0x8_0000_0000_0001))))
+    );
+    assert_eq!(
+        parse64(b"nan:0x8000000000001"),
+        Ok(Some((false, Special::Nan(0x8_0000_0000_0001))))
+    );
+    assert_eq!(
+        parse64(b"NAN:0XFFFFFFFFFFFFF"),
+        Ok(Some((false, Special::Nan(0xf_ffff_ffff_ffff))))
+    );
+    assert_eq!(
+        parse64(b"nan:0x0"),
+        Ok(Some((false, Special::Nan(1 << 51))))
+    );
+    assert_eq!(parse64(b"-snan:0x1"), Ok(Some((true, Special::Nan(1)))));
+    assert_eq!(
+        parse64(b"sNaN:0x7ffffffffffff"),
+        Ok(Some((false, Special::Nan(0x7_ffff_ffff_ffff))))
+    );
+    assert_eq!(
+        parse_special(b"snan:0x1_0", true, 23),
+        Ok(Some((false, Special::Nan(0x10))))
+    );
+
+    // invalid payloads
+    assert_eq!(
+        parse64(b"nan:0x10000000000000"),
+        err(NanPayloadOverflow, 4, None)
+    );
+    assert_eq!(
+        parse64(b"snan:0x8000000000000"),
+        err(NanPayloadOverflow, 5, None)
+    );
+    assert_eq!(
+        parse64(b"nan:0x1000000000000000000000001"),
+        err(NanPayloadOverflow, 4, None)
+    );
+    assert_eq!(
+        parse_special(b"nan:0x800000", false, 23),
+        err(NanPayloadOverflow, 4, None)
+    );
+    assert_eq!(parse64(b"snan:0x0"), err(ZeroSignalingNan, 5, None));
+    assert_eq!(parse64(b"-snan"), err(ZeroSignalingNan, 5, None));
+
+    // payload syntax errors
+    assert_eq!(
+        parse64(b"nan:"),
+        err(MissingPrefix, 4, Some(EXPECTED_PREFIX))
+    );
+    assert_eq!(
+        parse64(b"nan:1"),
+        err(MissingPrefix, 4, Some(EXPECTED_PREFIX))
+    );
+    assert_eq!(
+        parse64(b"nan:0x"),
+        err(MissingDigits, 6, Some(EXPECTED_HEX_DIGIT))
+    );
+    assert_eq!(
+        parse64(b"nan:0x_1"),
+        err(MisplacedUnderscore, 6, Some(EXPECTED_HEX_DIGIT))
+    );
+    assert_eq!(
+        parse64(b"nan:0x1_0"),
+        err(MisplacedUnderscore, 7, Some(EXPECTED_END))
+    );
+    assert_eq!(
+        parse64(b"nan:0x1p0"),
+        err(TrailingCharacters, 7, Some(EXPECTED_END))
+    );
+    assert_eq!(
+        parse64(b"nan:0x1:0x2"),
+        err(TrailingCharacters, 7, Some(EXPECTED_END))
+    );
+}
+
+// finds the byte offset of the first hex digit in `s` with any non-zero bit below 2^lsbexp,
+// i.e. a bit that would be lost. `s` should be a valid literal.
+//
+// this is written with plain loops so that it can be used in const fns.
+const fn locate_lost_bits(s: &[u8], lsbexp: isize) -> Option<usize> {
+    // find the start of digits and the exponent marker,
+    // and count the integral digits on the way
+    let mut start = 0;
+    while start < s.len() && s[start] != b'x' && s[start] != b'X' {
+        start += 1;
+    }
+    start += 1;
+    let mut marker = start;
+    let mut nintegral = 0isize;
+    let mut dot_seen = false;
+    while marker < s.len() && s[marker] != b'p' && s[marker] != b'P' {
+        match s[marker] {
+            b'.' => dot_seen = true,
+            b'_' => {}
+            _ if !dot_seen => nintegral += 1,
+            _ => {}
+        }
+        marker += 1;
+    }
+    if marker >= s.len() {
+        return None;
+    }
+
+    // read the exponent
+    let mut exponent = 0isize;
+    let mut i = marker + 1;
+    while i < s.len() {
+        if let Some(digit) = (s[i] as char).to_digit(10) {
+            exponent = exponent.saturating_mul(10).saturating_add(digit as isize);
+        }
+        i += 1;
+    }
+    if marker + 1 < s.len() && s[marker + 1] == b'-' {
+        exponent = -exponent;
+    }
+
+    // the j-th digit (excluding underscores) has bits from 2^(exponent + 4 * (nintegral - 1 - j))
+    let mut digitexp = exponent.saturating_add(4 * (nintegral - 1));
+    let mut i = start;
+    while i < marker {
+        if let Some(digit) = (s[i] as char).to_digit(16) {
+            let nlost = lsbexp.saturating_sub(digitexp);
+            let nlost = if nlost < 0 {
+                0
+            } else if nlost > 4 {
+                4
+            } else {
+                nlost
+            };
+            if digit & ((1 << nlost) - 1) != 0 {
+                return Some(i);
+            }
+            digitexp = digitexp.saturating_sub(4);
+        }
+        i += 1;
+    }
+    None
+}
+
+#[test]
+fn test_locate_lost_bits() {
+    assert_eq!(locate_lost_bits(b"0x1.99999bp-4", -27), Some(9));
+    assert_eq!(locate_lost_bits(b"0x1.999998000001p-4", -27), Some(15));
+    assert_eq!(locate_lost_bits(b"-0x1_9.0_1p+4", 5), Some(5));
+    assert_eq!(locate_lost_bits(b"-0x1_8.0_1p+4", 1), Some(9));
+    assert_eq!(locate_lost_bits(b"0x10.8P-1", 0), Some(5));
+    assert_eq!(locate_lost_bits(b"0x.0001P-1", -17), None);
+    assert_eq!(locate_lost_bits(b"0x.0001P-1", -16), Some(6));
+    assert_eq!(locate_lost_bits(b"0x1000000000000000001p0", 4), Some(20));
+}
+
+impl ParseHexfError {
+    // fills the offset of the lost bits in the input, if any.
+    const fn locate(mut self, s: &[u8]) -> Self {
+        if let Some((_, lsbexp)) = self.lost {
+            self.offset = locate_lost_bits(s, lsbexp);
+        }
+        self
+    }
+}
+
+// rounds `mantissa * 2^exponent` to an IEEE 754 binary interchange format with given field widths,
+// and returns its bits and also an error describing the loss if the result is not exact.
+const fn round_ieee(
+    negative: bool,
+    mantissa: u128,
+    exponent: isize,
+    rounding: RoundingMode,
+    exponent_bits: u32,
+    fraction_bits: u32,
+) -> (u128, Option<ParseHexfError>) {
+    let format = BinaryFormat::ieee(exponent_bits, fraction_bits);
+    round_binary(negative, mantissa, exponent, rounding, &format)
+}
+
+// rounds `mantissa * 2^exponent` to given binary format,
+// and returns its bits and also an error describing the loss if the result is not exact.
+//
+// this directly assembles the bits without any floating point arithmetic,
+// so it can be used in const fns and doesn't depend on the platform.
+const fn round_binary(
+    negative: bool,
+    mantissa: u128,
+    exponent: isize,
+    rounding: RoundingMode,
+    format: &BinaryFormat,
+) -> (u128, Option<ParseHexfError>) {
+    let exponent_bits = format.exponent_bits;
+    let fraction_bits = format.fraction_bits;
+    let sign = (negative as u128) << (exponent_bits + fraction_bits);
+    if mantissa == 0 {
+        return (sign, None);
+    }
+
+    // the largest biased exponent and bits of finite numbers.
+    // the maximal biased exponent is reserved for infinities and NaNs if the format has
+    // infinities, otherwise it is used for finite numbers except for the all-ones NaN if any.
+    let bias = format.bias as isize;
+    let allones = (1 << (exponent_bits + fraction_bits)) - 1;
+    let (maxbiased, maxfinite) = if format.has_infinity {
+        let infinity = ((1 << exponent_bits) - 1) << fraction_bits;
+        ((1 << exponent_bits) - 2, infinity - 1)
+    } else if format.has_nan {
+        ((1 << exponent_bits) - 1, allones - 1)
+    } else {
+        ((1 << exponent_bits) - 1, allones)
+    };
+
+    // normalize the exponent that the number is (1.xxxx * 2^normalexp),
+    // and determine the exponent of the least significant bit we can keep.
+    // it is fixed relative to normalexp for normal numbers,
+    // and fixed to the exponent of the minimal denormal number (`minexp`) otherwise.
+    let minexp = 1 - bias - fraction_bits as isize;
+    let maxexp = maxbiased - bias; // the exponent of the largest finite number
+
+    // guard the exponent with the definitely safe range.
+    // any value beyond this range is far outside of the target type anyway,
+    // so clamping doesn't change the rounded result.
+    let exponent = if exponent < minexp - 0x100 {
+        minexp - 0x100
+    } else if exponent > maxexp + 0x100 {
+        maxexp + 0x100
+    } else {
+        exponent
+    };
+    let normalexp = exponent + (127 - mantissa.leading_zeros() as isize);
+    let lsbexp = normalexp - fraction_bits as isize;
+    let lsbexp = if lsbexp < minexp { minexp } else { lsbexp };
+
+    // split the mantissa into the kept bits, the first discarded bit (`half`)
+    // and whether any other discarded bit is set (`sticky`)
+    let shift = lsbexp - exponent;
+    let (mut kept, keptexp, half, sticky) = if shift <= 0 {
+        // the mantissa already fits in the target type
+        (mantissa, exponent, false, false)
+    } else if shift < 128 {
+        let rest = mantissa << (128 - shift);
+        (mantissa >> shift, lsbexp, rest >> 127 != 0, rest << 1 != 0)
+    } else if shift == 128 {
+        (0, lsbexp, mantissa >> 127 != 0, mantissa << 1 != 0)
+    } else {
+        (0, lsbexp, false, true)
+    };
+
+    let loss = if kept == 0 {
+        Some(UNDERFLOW)
+    } else if half || sticky {
+        let precision = (normalexp - lsbexp + 1) as u32;
+        Some(ParseHexfError {
+            lost: Some((precision, lsbexp)),
+            ..INEXACT
+        })
+    } else {
+        None
+    };
+
+    // `kept` has at most (fraction_bits + 1) bits so this cannot overflow
+    if rounding.round_up(negative, kept & 1 != 0, half, sticky) {
+        kept += 1;
+    }
+    if kept == 0 {
+        return (sign, loss);
+    }
+
+    // the number may exceed the maximal finite number, possibly due to the rounding.
+    // this is either an infinity or the largest finite number if the format has no infinities.
+    let overflow = if format.has_infinity && rounding.overflows_to_infinity(negative) {
+        (sign | (maxfinite + 1), Some(OVERFLOW))
+    } else {
+        (sign | maxfinite, Some(OVERFLOW))
+    };
+    let msb = 127 - kept.leading_zeros() as isize;
+    if keptexp + msb > maxexp {
+        return overflow;
+    }
+
+    // shift `kept` so that its most significant bit is at `fraction_bits`,
+    // unless the number is subnormal where `keptexp` should be `minexp` instead.
+    // rounding up can only carry into a power of two, so shifting right is exact.
+    let (kept, keptexp) = if msb > fraction_bits as isize {
+        (kept >> 1, keptexp + 1)
+    } else {
+        let nshift = fraction_bits as isize - msb;
+        let nshift = if nshift > keptexp - minexp {
+            keptexp - minexp
+        } else {
+            nshift
+        };
+        (kept << nshift, keptexp - nshift)
+    };
+
+    // the implicit leading bit of normal numbers is added to the biased exponent,
+    // which is therefore one less than the actual biased exponent here
+    let biased = (keptexp - minexp) as u128;
+    let bits = (biased << fraction_bits) + kept;
+    if bits > maxfinite {
+        // only possible when the largest biased exponent also has a NaN
+        return overflow;
+    }
+    (sign | bits, loss)
+}
+
+// returns the bits of the special value with given sign and field widths.
+const fn special_ieee(
+    negative: bool,
+    special: Special,
+    exponent_bits: u32,
+    fraction_bits: u32,
+) -> u128 {
+    let sign = (negative as u128) << (exponent_bits + fraction_bits);
+    let infinity = ((1 << exponent_bits) - 1) << fraction_bits;
+    match special {
+        Special::Infinity => sign | infinity,
+        Special::Nan(fraction) => sign | infinity | fraction,
+    }
+}
+
+macro_rules! define_convert {
+    (
+        $round:ident, $convert:ident, $special:ident =>
+        $f:ident($bits:ident, $exponent_bits:expr, $fraction_bits:expr)
+    ) => {
+        // rounds `mantissa * 2^exponent` to the target type,
+        // and also returns an error describing the loss if the result is not exact.
+        const fn $round(
+            negative: bool,
+            mantissa: u128,
+            exponent: isize,
+            rounding: RoundingMode,
+        ) -> ($f, Option<ParseHexfError>) {
+            let (bits, loss) = round_ieee(
+                negative,
+                mantissa,
+                exponent,
+                rounding,
+                $exponent_bits,
+                $fraction_bits,
+            );
+            ($f::from_bits(bits as $bits), loss)
+        }
+
+        // returns the special value with given sign.
+        const fn $special(negative: bool, special: Special) -> $f {
+            $f::from_bits(special_ieee(negative, special, $exponent_bits, $fraction_bits) as $bits)
+        }
+
+        const fn $convert(
+            negative: bool,
+            mantissa: u128,
+            exponent: isize,
+        ) -> Result<$f, ParseHexfError> {
+            match $round(negative, mantissa, exponent, RoundingMode::TowardZero) {
+                (value, None) => Ok(value),
+                (_, Some(err)) => Err(err),
+            }
+        }
+    };
+}
+
+define_convert!(round_hexf32, convert_hexf32, special_hexf32 => f32(u32, 8, 23));
+define_convert!(round_hexf64, convert_hexf64, special_hexf64 => f64(u64, 11, 52));
+
+#[test]
+fn test_convert_hexf32() {
+    let inexact = |precision, lsbexp| {
+        Err(ParseHexfError {
+            lost: Some((precision, lsbexp)),
+            ..INEXACT
+        })
+    };
+
+    assert_eq!(convert_hexf32(false, 0, 0), Ok(0.0));
+    assert_eq!(convert_hexf32(false, 1, 0), Ok(1.0));
+    assert_eq!(convert_hexf32(false, 10, 0), Ok(10.0));
+    assert_eq!(convert_hexf32(false, 10, 1), Ok(20.0));
+    assert_eq!(convert_hexf32(false, 10, -1), Ok(5.0));
+    assert_eq!(convert_hexf32(true, 0, 0), Ok(-0.0));
+    assert_eq!(convert_hexf32(true, 1, 0), Ok(-1.0));
+
+    // negative zeroes
+    assert_eq!(convert_hexf32(false, 0, 0).unwrap().signum(), 1.0);
+    assert_eq!(convert_hexf32(true, 0, 0).unwrap().signum(), -1.0);
+
+    // normal truncation
+    assert_eq!(
+        convert_hexf32(false, 0x0000_0000_00ff_ffff, 0),
+        Ok(16777215.0)
+    );
+    assert_eq!(
+        convert_hexf32(false, 0x0000_0000_01ff_ffff, 0),
+        inexact(24, 1)
+    );
+    assert_eq!(
+        convert_hexf32(false, 0xffff_ff00_0000_0000, -40),
+        Ok(16777215.0)
+    );
+    assert_eq!(
+        convert_hexf32(false, 0xffff_ff80_0000_0000, -40),
+        inexact(24, 0)
+    );
+
+    // denormal truncation
+    assert!(convert_hexf32(false, 0x0000_0000_007f_ffff, -149).is_ok());
     assert!(convert_hexf32(false, 0x0000_0000_00ff_ffff, -150).is_err());
     assert!(convert_hexf32(false, 0x0000_0000_00ff_fffe, -150).is_ok());
     assert!(convert_hexf32(false, 0xffff_ff00_0000_0000, -190).is_err());
@@ -408,6 +1646,10 @@ fn test_convert_hexf32() {
     assert!(convert_hexf32(false, 0x0000_0000_0000_0003, -151).is_err());
     assert!(convert_hexf32(false, 0x8000_0000_0000_0000, -212).is_ok());
     assert!(convert_hexf32(false, 0x8000_0000_0000_0000, -213).is_err());
+    assert_eq!(
+        convert_hexf32(false, 0x8000_0000_0000_0000, -212),
+        Ok(f32::from_bits(1))
+    );
 
     // maximum
     assert_eq!(
@@ -416,19 +1658,19 @@ fn test_convert_hexf32() {
     );
     assert_eq!(
         convert_hexf32(false, 0x0000_0000_01ff_ffff, 104),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf32(false, 0x0000_0000_01ff_fffe, 104),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf32(false, 0x0000_0000_0000_0001, 128),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf32(false, 0x8000_0000_0000_0000, 65),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf32(false, 0xffff_ff00_0000_0000, 64),
@@ -436,12 +1678,19 @@ fn test_convert_hexf32() {
     );
     assert_eq!(
         convert_hexf32(false, 0xffff_ff80_0000_0000, 64),
-        Err(INEXACT)
+        inexact(24, 104)
     );
 }
 
 #[test]
 fn test_convert_hexf64() {
+    let inexact = |precision, lsbexp| {
+        Err(ParseHexfError {
+            lost: Some((precision, lsbexp)),
+            ..INEXACT
+        })
+    };
+
     assert_eq!(convert_hexf64(false, 0, 0), Ok(0.0));
     assert_eq!(convert_hexf64(false, 1, 0), Ok(1.0));
     assert_eq!(convert_hexf64(false, 10, 0), Ok(10.0));
@@ -461,7 +1710,7 @@ fn test_convert_hexf64() {
     );
     assert_eq!(
         convert_hexf64(false, 0x003f_ffff_ffff_ffff, 0),
-        Err(INEXACT)
+        inexact(53, 1)
     );
     assert_eq!(
         convert_hexf64(false, 0xffff_ffff_ffff_f800, -11),
@@ -469,7 +1718,7 @@ fn test_convert_hexf64() {
     );
     assert_eq!(
         convert_hexf64(false, 0xffff_ffff_ffff_fc00, -11),
-        Err(INEXACT)
+        inexact(53, 0)
     );
 
     // denormal truncation
@@ -488,6 +1737,10 @@ fn test_convert_hexf64() {
     assert!(convert_hexf64(false, 0x0000_0000_0000_0003, -1076).is_err());
     assert!(convert_hexf64(false, 0x8000_0000_0000_0000, -1137).is_ok());
     assert!(convert_hexf64(false, 0x8000_0000_0000_0000, -1138).is_err());
+    assert_eq!(
+        convert_hexf64(false, 0x8000_0000_0000_0000, -1137),
+        Ok(f64::from_bits(1))
+    );
 
     // maximum
     assert_eq!(
@@ -496,19 +1749,19 @@ fn test_convert_hexf64() {
     );
     assert_eq!(
         convert_hexf64(false, 0x003f_ffff_ffff_ffff, 971),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf64(false, 0x003f_ffff_ffff_fffe, 971),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf32(false, 0x0000_0000_0000_0001, 1024),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf32(false, 0x8000_0000_0000_0000, 961),
-        Err(INEXACT)
+        Err(OVERFLOW)
     );
     assert_eq!(
         convert_hexf64(false, 0xffff_ffff_ffff_f800, 960),
@@ -516,22 +1769,383 @@ fn test_convert_hexf64() {
     );
     assert_eq!(
         convert_hexf64(false, 0xffff_ffff_ffff_fc00, 960),
-        Err(INEXACT)
+        inexact(53, 971)
+    );
+}
+
+#[test]
+fn test_round_hexf32() {
+    use ParseHexfErrorKind::*;
+    use RoundingMode::*;
+    let round32 = |negative, mantissa, exponent, mode| {
+        let (value, err) = round_hexf32(negative, mantissa, exponent, mode);
+        (value, err.map(|err: ParseHexfError| err.kind))
+    };
+    const ALL: [RoundingMode; 6] = [
+        NearestTiesEven,
+        NearestTiesAway,
+        TowardZero,
+        TowardPositive,
+        TowardNegative,
+        ToOdd,
+    ];
+
+    // exact values are not affected by the rounding mode
+    for &mode in &ALL {
+        assert_eq!(round32(false, 0, 0, mode), (0.0, None));
+        assert_eq!(round32(false, 10, -1, mode), (5.0, None));
+        assert_eq!(round32(true, 0x00ff_ffff, 104, mode), (-f32::MAX, None));
+        assert_eq!(round32(false, 1, -149, mode), (f32::from_bits(1), None));
+        assert_eq!(round32(true, 0, 0, mode).0.signum(), -1.0);
+    }
+
+    // 0x2000001 (below a tie), 0x2000002 (tie), 0x2000003 (above a tie)
+    // and 0x2000006 (tie with an odd truncated mantissa), all scaled by 2^-25
+    let up = 1.0 + f32::EPSILON;
+    let up2 = 1.0 + 2.0 * f32::EPSILON;
+    let cases = [
+        (NearestTiesEven, [1.0, 1.0, up, up2]),
+        (NearestTiesAway, [1.0, up, up, up2]),
+        (TowardZero, [1.0, 1.0, 1.0, up]),
+        (TowardPositive, [up, up, up, up2]),
+        (TowardNegative, [1.0, 1.0, 1.0, up]),
+        (ToOdd, [up, up, up, up]),
+    ];
+    for &(mode, expected) in &cases {
+        for (&mantissa, &expected) in [0x2000001, 0x2000002, 0x2000003, 0x2000006]
+            .iter()
+            .zip(&expected)
+        {
+            assert_eq!(
+                round32(false, mantissa, -25, mode),
+                (expected, Some(Inexact)),
+                "{:#x} with {:?}",
+                mantissa,
+                mode
+            );
+        }
+    }
+
+    // directed modes depend on the sign
+    assert_eq!(
+        round32(true, 0x1000001, -24, TowardPositive),
+        (-1.0, Some(Inexact))
+    );
+    assert_eq!(
+        round32(true, 0x1000001, -24, TowardNegative),
+        (-up, Some(Inexact))
+    );
+
+    // overflow
+    assert_eq!(
+        round32(false, 1, 128, NearestTiesEven),
+        (f32::INFINITY, Some(Overflow))
+    );
+    assert_eq!(
+        round32(true, 1, 128, NearestTiesAway),
+        (f32::NEG_INFINITY, Some(Overflow))
+    );
+    assert_eq!(
+        round32(false, 1, 128, TowardZero),
+        (f32::MAX, Some(Overflow))
+    );
+    assert_eq!(
+        round32(false, 1, 128, TowardPositive),
+        (f32::INFINITY, Some(Overflow))
+    );
+    assert_eq!(
+        round32(true, 1, 128, TowardPositive),
+        (-f32::MAX, Some(Overflow))
+    );
+    assert_eq!(
+        round32(false, 1, 128, TowardNegative),
+        (f32::MAX, Some(Overflow))
+    );
+    assert_eq!(
+        round32(true, 1, 128, TowardNegative),
+        (f32::NEG_INFINITY, Some(Overflow))
+    );
+    assert_eq!(round32(true, 1, 128, ToOdd), (-f32::MAX, Some(Overflow)));
+    assert_eq!(
+        round32(false, 1, isize::MAX, ToOdd),
+        (f32::MAX, Some(Overflow))
+    );
+
+    // overflow caused by rounding up
+    assert_eq!(
+        round32(false, 0x01ff_ffff, 103, NearestTiesEven),
+        (f32::INFINITY, Some(Overflow))
+    );
+    assert_eq!(
+        round32(false, 0x01ff_ffff, 103, TowardZero),
+        (f32::MAX, Some(Inexact))
+    );
+
+    // underflow
+    let min = f32::from_bits(1);
+    assert_eq!(
+        round32(false, 1, -150, NearestTiesEven),
+        (0.0, Some(Underflow))
+    );
+    assert_eq!(
+        round32(false, 3, -151, NearestTiesEven),
+        (min, Some(Underflow))
+    );
+    assert_eq!(
+        round32(false, 1, -150, NearestTiesAway),
+        (min, Some(Underflow))
+    );
+    assert_eq!(
+        round32(false, 1, -151, NearestTiesAway),
+        (0.0, Some(Underflow))
+    );
+    assert_eq!(round32(false, 1, -1000, TowardZero), (0.0, Some(Underflow)));
+    assert_eq!(
+        round32(false, 1, -1000, TowardPositive),
+        (min, Some(Underflow))
+    );
+    assert_eq!(round32(true, 1, -1000, TowardPositive).0.signum(), -1.0);
+    assert_eq!(
+        round32(true, 1, -1000, TowardNegative),
+        (-min, Some(Underflow))
+    );
+    assert_eq!(round32(false, 1, isize::MIN, ToOdd), (min, Some(Underflow)));
+
+    // rounding into the normal range
+    assert_eq!(
+        round32(false, 0x00ff_ffff, -150, NearestTiesEven),
+        (f32::MIN_POSITIVE, Some(Inexact))
+    );
+    assert_eq!(
+        round32(false, 0x00ff_ffff, -150, TowardZero),
+        (f32::MIN_POSITIVE - min, Some(Inexact))
+    );
+}
+
+#[test]
+fn test_round_hexf64() {
+    use ParseHexfErrorKind::*;
+    use RoundingMode::*;
+    let round64 = |negative, mantissa, exponent, mode| {
+        let (value, err) = round_hexf64(negative, mantissa, exponent, mode);
+        (value, err.map(|err: ParseHexfError| err.kind))
+    };
+
+    let one_up = 1.0 + f64::EPSILON;
+    assert_eq!(
+        round64(false, 1 << 53 | 1, -53, NearestTiesEven),
+        (1.0, Some(Inexact))
+    );
+    assert_eq!(
+        round64(false, 1 << 53 | 1, -53, NearestTiesAway),
+        (one_up, Some(Inexact))
+    );
+    assert_eq!(
+        round64(false, 1 << 53 | 3, -53, NearestTiesEven),
+        (1.0 + 2.0 * f64::EPSILON, Some(Inexact))
+    );
+    assert_eq!(
+        round64(false, 1 << 63 | 1, -63, TowardZero),
+        (1.0, Some(Inexact))
+    );
+    assert_eq!(
+        round64(false, 1 << 63 | 1, -63, TowardPositive),
+        (one_up, Some(Inexact))
+    );
+    assert_eq!(
+        round64(true, 1 << 63 | 1, -63, TowardNegative),
+        (-one_up, Some(Inexact))
+    );
+    assert_eq!(
+        round64(false, 1 << 63 | 1, -63, ToOdd),
+        (one_up, Some(Inexact))
+    );
+    assert_eq!(
+        round64(false, 1 << 63 | 1 << 11, -63, ToOdd),
+        (one_up, None)
+    );
+
+    assert_eq!(
+        round64(false, 1, 1024, NearestTiesEven),
+        (f64::INFINITY, Some(Overflow))
+    );
+    assert_eq!(
+        round64(false, 1, 1024, TowardZero),
+        (f64::MAX, Some(Overflow))
+    );
+    assert_eq!(
+        round64(true, 1, 1024, TowardPositive),
+        (-f64::MAX, Some(Overflow))
+    );
+    assert_eq!(
+        round64(false, 1, -1075, NearestTiesEven),
+        (0.0, Some(Underflow))
     );
+    assert_eq!(
+        round64(false, 1, -1075, NearestTiesAway),
+        (f64::from_bits(1), Some(Underflow))
+    );
+    assert_eq!(
+        round64(false, 1, -1075, ToOdd),
+        (f64::from_bits(1), Some(Underflow))
+    );
+}
+
+#[test]
+fn test_round_hexf_int_cast() {
+    // integer-to-float casts are rounded to the nearest, ties to even
+    for state in XorShift64(0x9e37_79b9_7f4a_7c15u64).take(100_000) {
+        let wide = (state as u128) << 64 | (state >> 7) as u128;
+        for &mantissa in &[
+            state as u128,
+            (state >> 37) as u128,
+            (state & !0xfff) as u128,
+            wide,
+            wide >> 11,
+            wide & !0xfff_ffff_ffff,
+        ] {
+            let (v32, _) = round_hexf32(false, mantissa, 0, RoundingMode::NearestTiesEven);
+            assert_eq!(
+                v32.to_bits(),
+                (mantissa as f32).to_bits(),
+                "{:#x}",
+                mantissa
+            );
+            let (v64, _) = round_hexf64(true, mantissa, 0, RoundingMode::NearestTiesEven);
+            assert_eq!(
+                v64.to_bits(),
+                (-(mantissa as f64)).to_bits(),
+                "{:#x}",
+                mantissa
+            );
+        }
+    }
 }
 
 /// Tries to parse a hexadecimal float literal to `f32`.
 /// The underscore is allowed only when `allow_underscore` is true.
+///
+/// Infinities and NaNs are also accepted as `inf`, `infinity` and `nan`,
+/// case-insensitively and optionally signed.
+/// NaNs can have an explicit payload as in `nan:0x1` (quiet) and `snan:0x1` (signaling),
+/// which is the fraction field without the quiet bit.
+/// It is an error to have a payload that doesn't fit or a zero payload for signaling NaNs.
 pub fn parse_hexf32(s: &str, allow_underscore: bool) -> Result<f32, ParseHexfError> {
+    if let Some((negative, special)) =
+        parse_special(s.as_bytes(), allow_underscore, f32::MANTISSA_DIGITS - 1)?
+    {
+        return Ok(special_hexf32(negative, special));
+    }
     let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
-    convert_hexf32(negative, mantissa, exponent)
+    convert_hexf32(negative, mantissa, exponent).map_err(|err| err.locate(s.as_bytes()))
 }
 
 /// Tries to parse a hexadecimal float literal to `f64`.
 /// The underscore is allowed only when `allow_underscore` is true.
+///
+/// Infinities and NaNs are also accepted as `inf`, `infinity` and `nan`,
+/// case-insensitively and optionally signed.
+/// NaNs can have an explicit payload as in `nan:0x1` (quiet) and `snan:0x1` (signaling),
+/// which is the fraction field without the quiet bit.
+/// It is an error to have a payload that doesn't fit or a zero payload for signaling NaNs.
 pub fn parse_hexf64(s: &str, allow_underscore: bool) -> Result<f64, ParseHexfError> {
+    if let Some((negative, special)) =
+        parse_special(s.as_bytes(), allow_underscore, f64::MANTISSA_DIGITS - 1)?
+    {
+        return Ok(special_hexf64(negative, special));
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    convert_hexf64(negative, mantissa, exponent).map_err(|err| err.locate(s.as_bytes()))
+}
+
+/// Same to `parse_hexf32` but can be used in constant expressions.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// const TENTH: f32 = match parse_hexf32_const("0x1.99999ap-4", false) {
+///     Ok(v) => v,
+///     Err(_) => panic!("invalid literal"),
+/// };
+/// assert_eq!(TENTH, 0.1f32);
+/// ```
+pub const fn parse_hexf32_const(s: &str, allow_underscore: bool) -> Result<f32, ParseHexfError> {
+    let s = s.as_bytes();
+    match parse_special(s, allow_underscore, f32::MANTISSA_DIGITS - 1) {
+        Ok(Some((negative, special))) => return Ok(special_hexf32(negative, special)),
+        Ok(None) => {}
+        Err(err) => return Err(err),
+    }
+    match parse(s, allow_underscore) {
+        Ok((negative, mantissa, exponent)) => match convert_hexf32(negative, mantissa, exponent) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(err.locate(s)),
+        },
+        Err(err) => Err(err),
+    }
+}
+
+/// Same to `parse_hexf64` but can be used in constant expressions.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// const TWELVE: f64 = match parse_hexf64_const("0x1.8p3", false) {
+///     Ok(v) => v,
+///     Err(_) => panic!("invalid literal"),
+/// };
+/// assert_eq!(TWELVE, 12.0f64);
+/// ```
+pub const fn parse_hexf64_const(s: &str, allow_underscore: bool) -> Result<f64, ParseHexfError> {
+    let s = s.as_bytes();
+    match parse_special(s, allow_underscore, f64::MANTISSA_DIGITS - 1) {
+        Ok(Some((negative, special))) => return Ok(special_hexf64(negative, special)),
+        Ok(None) => {}
+        Err(err) => return Err(err),
+    }
+    match parse(s, allow_underscore) {
+        Ok((negative, mantissa, exponent)) => match convert_hexf64(negative, mantissa, exponent) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(err.locate(s)),
+        },
+        Err(err) => Err(err),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to `f32`, rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexf32` and never rounded.
+pub fn parse_hexf32_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<f32, ParseHexfError> {
+    if let Some((negative, special)) =
+        parse_special(s.as_bytes(), allow_underscore, f32::MANTISSA_DIGITS - 1)?
+    {
+        return Ok(special_hexf32(negative, special));
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    Ok(round_hexf32(negative, mantissa, exponent, rounding).0)
+}
+
+/// Tries to parse a hexadecimal float literal to `f64`, rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexf64` and never rounded.
+pub fn parse_hexf64_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<f64, ParseHexfError> {
+    if let Some((negative, special)) =
+        parse_special(s.as_bytes(), allow_underscore, f64::MANTISSA_DIGITS - 1)?
+    {
+        return Ok(special_hexf64(negative, special));
+    }
     let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
-    convert_hexf64(negative, mantissa, exponent)
+    Ok(round_hexf64(negative, mantissa, exponent, rounding).0)
 }
 
 #[test]
@@ -539,4 +2153,240 @@ fn test_parse_hexf() {
     // issues
     // #6 (https://github.com/lifthrasiir/hexf/issues/6)
     assert!(parse_hexf64("0x.000000000000000000102", false).is_err());
+
+    // special values
+    assert_eq!(parse_hexf32("inf", false), Ok(f32::INFINITY));
+    assert_eq!(parse_hexf32("-Infinity", false), Ok(f32::NEG_INFINITY));
+    assert_eq!(parse_hexf64("+INF", false), Ok(f64::INFINITY));
+    assert_eq!(parse_hexf64("-inf", true), Ok(f64::NEG_INFINITY));
+    assert!(parse_hexf32("NaN", false).unwrap().is_nan());
+    assert!(parse_hexf64("nan", false).unwrap().is_sign_positive());
+    assert!(parse_hexf64("-nan", false).unwrap().is_sign_negative());
+    assert_eq!(
+        parse_hexf64_rounded("-inf", false, RoundingMode::TowardZero),
+        Ok(f64::NEG_INFINITY)
+    );
+    assert!(parse_hexf32_rounded("nan", false, RoundingMode::ToOdd)
+        .unwrap()
+        .is_nan());
+    assert_eq!(
+        parse_hexf64("nan:0x8000000000001", false).map(f64::to_bits),
+        Ok(0x7ff8_0000_0000_0001)
+    );
+    assert_eq!(
+        parse_hexf64("-snan:0x1", false).map(f64::to_bits),
+        Ok(0xfff0_0000_0000_0001)
+    );
+    assert_eq!(
+        parse_hexf32("nan:0x1_2345", true).map(f32::to_bits),
+        Ok(0x7fc1_2345)
+    );
+    assert_eq!(
+        parse_hexf32_rounded("-snan:0x3fffff", false, RoundingMode::NearestTiesEven)
+            .map(f32::to_bits),
+        Ok(0xffbf_ffff)
+    );
+    assert_eq!(
+        parse_hexf32("nan:0x8000000000001", false)
+            .unwrap_err()
+            .kind(),
+        ParseHexfErrorKind::NanPayloadOverflow
+    );
+    assert_eq!(
+        parse_hexf64("snan:0x0", false).unwrap_err().kind(),
+        ParseHexfErrorKind::ZeroSignalingNan
+    );
+    assert_eq!(
+        parse_hexf64("infinite", false).unwrap_err().kind(),
+        ParseHexfErrorKind::MissingPrefix
+    );
+
+    // exactness only depends on the value, not the number of digits
+    assert_eq!(parse_hexf64("0x10000000000000000000p-76", false), Ok(1.0));
+    assert_eq!(
+        parse_hexf64("0x10000000000000000000p-80", false),
+        Ok(0.0625)
+    );
+    assert_eq!(parse_hexf64("0x1.00000000000000000000p0", false), Ok(1.0));
+    assert_eq!(
+        parse_hexf64("0x0.00000000000000000000000000001p116", false),
+        Ok(1.0)
+    );
+    assert_eq!(
+        parse_hexf32("0xffffff00000000000000000000p+24", false),
+        Ok(f32::MAX)
+    );
+    assert_eq!(
+        parse_hexf64("0x10000000000000000000000000001p-116", false)
+            .unwrap_err()
+            .kind(),
+        ParseHexfErrorKind::Inexact
+    );
+}
+
+#[test]
+fn test_parse_hexf_const() {
+    const ONE_AND_HALF: Result<f64, ParseHexfError> = parse_hexf64_const("0x1.8p0", false);
+    const INEXACT32: Result<f32, ParseHexfError> = parse_hexf32_const("0x1.99999bp-4", false);
+    assert_eq!(ONE_AND_HALF, Ok(1.5));
+    assert_eq!(INEXACT32, parse_hexf32("0x1.99999bp-4", false));
+
+    // the const versions should agree with the runtime versions bit by bit
+    let cases = [
+        "0x0p0",
+        "-0x0p0",
+        "0x1.99999ap-4",
+        "-0x1.999999999999ap-4",
+        "0x1.fffffep+127",
+        "0x1.fffffffffffffp+1023",
+        "0x1p128",
+        "0x1p1024",
+        "0x1p-126",
+        "0x1p-149",
+        "0x1p-150",
+        "0x0.fffffep-126",
+        "0x1p-1022",
+        "0x1p-1074",
+        "0x1p-1075",
+        "0x1.8p-1074",
+        "0x0.fffffffffffffp-1022",
+        "0x1_0.0_1p-4",
+        "0x10000000000000000000p-76",
+        "0x1.00000000000008p0",
+        "-inf",
+        "nan",
+        "-snan:0x1",
+        "nan:0x8000000000001",
+        "snan:0x0",
+        "0x1.8q",
+        "",
+    ];
+    for &s in &cases {
+        assert_eq!(
+            parse_hexf32_const(s, true).map(f32::to_bits),
+            parse_hexf32(s, true).map(f32::to_bits),
+            "{}",
+            s
+        );
+        assert_eq!(
+            parse_hexf64_const(s, true).map(f64::to_bits),
+            parse_hexf64(s, true).map(f64::to_bits),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_parse_hexf_error_kind() {
+    use ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf64(s, true).unwrap_err().kind();
+
+    assert_eq!(kind(""), Empty);
+    assert_eq!(kind("1.0p0"), MissingPrefix);
+    assert_eq!(kind("0x.p0"), MissingDigits);
+    assert_eq!(kind("0x1.0p"), MissingDigits);
+    assert_eq!(kind("0x1.0"), MissingExponent);
+    assert_eq!(kind("0x1._0p0"), MisplacedUnderscore);
+    assert_eq!(kind("0x1.0p0f64"), TrailingCharacters);
+    assert_eq!(kind("0x1p1024"), Overflow);
+    assert_eq!(kind("-0x1p99999999999999999999"), Overflow);
+    assert_eq!(kind("0x1p-1075"), Underflow);
+    assert_eq!(kind("0x1.fp-1075"), Underflow);
+    assert_eq!(kind("0x1.8p-1074"), Inexact);
+    assert_eq!(kind("0x1.00000000000008p0"), Inexact);
+    assert_eq!(
+        parse_hexf32("0x1p-150", false).unwrap_err().to_string(),
+        "cannot represent float in target type: too small"
+    );
+}
+
+#[test]
+fn test_parse_hexf_error_offset() {
+    let err = parse_hexf64("-0x1.8p+1_0", false).unwrap_err();
+    assert_eq!(err.kind(), ParseHexfErrorKind::MisplacedUnderscore);
+    assert_eq!(err.offset(), Some(9));
+    assert_eq!(err.expected(), Some("end of input"));
+
+    let err = parse_hexf64("0x1.8p", false).unwrap_err();
+    assert_eq!(err.offset(), Some(6));
+    assert_eq!(err.expected(), Some("decimal exponent digit"));
+    assert_eq!(
+        err.to_string(),
+        "invalid hexadecimal float literal: missing digits \
+         (expected decimal exponent digit at byte 6)"
+    );
+
+    let err = parse_hexf64("", false).unwrap_err();
+    assert_eq!(err.offset(), Some(0));
+    assert_eq!(err.expected(), None);
+    assert_eq!(err.to_string(), "cannot parse float from empty string");
+
+    let err = parse_hexf64("0x1p1024", false).unwrap_err();
+    assert_eq!(err.offset(), None);
+    assert_eq!(err.expected(), None);
+
+    let err = parse_hexf32("0x1.99999bp-4", false).unwrap_err();
+    assert_eq!(err.kind(), ParseHexfErrorKind::Inexact);
+    assert_eq!(err.offset(), Some(9));
+    assert_eq!(err.expected(), None);
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 24 significant bits fit, bits from the digit at byte 9 are lost)"
+    );
+    let err = parse_hexf64("-0x1.8p-1074", false).unwrap_err();
+    assert_eq!(err.offset(), Some(5));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 1 significant bit fit, bits from the digit at byte 5 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexf_rounded() {
+    use RoundingMode::*;
+
+    assert_eq!(
+        parse_hexf32_rounded("0x1.99999ap-4", false, TowardZero),
+        Ok(0.1f32)
+    );
+    assert_eq!(
+        parse_hexf32_rounded("0x1.99999_98p-4", true, NearestTiesEven),
+        Ok(0.1f32)
+    );
+    assert_eq!(
+        parse_hexf32_rounded("0x1.999999p-4", false, TowardZero),
+        Ok(f32::from_bits(0.1f32.to_bits() - 1))
+    );
+    assert_eq!(
+        parse_hexf64_rounded("0x1.999999999999999p-4", false, NearestTiesEven),
+        Ok(0.1f64)
+    );
+    assert_eq!(
+        parse_hexf64_rounded("-0x1.999999999999999p-4", false, TowardZero),
+        Ok(-f64::from_bits(0.1f64.to_bits() - 1))
+    );
+    assert_eq!(
+        parse_hexf64_rounded("0x1p-1075", false, NearestTiesEven),
+        Ok(0.0)
+    );
+    assert_eq!(
+        parse_hexf64_rounded("0x1p-1075", false, TowardPositive),
+        Ok(f64::from_bits(1))
+    );
+    assert_eq!(
+        parse_hexf64_rounded("0x1p1024", false, NearestTiesEven),
+        Ok(f64::INFINITY)
+    );
+    assert_eq!(
+        parse_hexf64_rounded("-0x1p99999999999999999999999", false, TowardZero),
+        Ok(-f64::MAX)
+    );
+    assert_eq!(
+        parse_hexf64_rounded("0x1p-99999999999999999999999", false, NearestTiesEven),
+        Ok(0.0)
+    );
+    assert!(parse_hexf64_rounded("0x1p", false, NearestTiesEven).is_err());
 }
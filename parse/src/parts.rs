@@ -0,0 +1,269 @@
+//! Parses hexadecimal float literals into their exact parts without any conversion.
+
+use crate::{
+    is_special, parse_truncated, ParseHexfError, INEXACT, OVERFLOW, UNDERFLOW, UNSUPPORTED_SPECIAL,
+};
+
+/// An exact value of a hexadecimal float literal, `(-1)^negative * significand * 2^exponent`.
+///
+/// This is returned by `parse_hexf_parts` as read from the literal, so the same value can have
+/// multiple representations (e.g. `0x1p0` and `0x10p-4`); `normalized` returns a canonical one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexFloatParts {
+    /// True if the literal is negative, including the negative zero.
+    pub negative: bool,
+    /// The significand as an integer.
+    pub significand: u128,
+    /// The binary exponent applied to the significand.
+    pub exponent: isize,
+}
+
+impl HexFloatParts {
+    /// Returns true if the value is a zero of either sign.
+    pub const fn is_zero(&self) -> bool {
+        self.significand == 0
+    }
+
+    /// Returns the canonical representation of the same value,
+    /// where the significand is odd (i.e. has no trailing zero bits)
+    /// or the significand and the exponent are both zero for zeroes.
+    /// Returns `None` if the resulting exponent doesn't fit in `isize`.
+    ///
+    /// ```rust
+    /// use hexf_parse::*;
+    /// let parts = parse_hexf_parts("-0x1.80p4", false).unwrap();
+    /// assert_eq!(
+    ///     parts.normalized(),
+    ///     Some(HexFloatParts { negative: true, significand: 3, exponent: 3 })
+    /// );
+    /// ```
+    pub const fn normalized(&self) -> Option<HexFloatParts> {
+        if self.significand == 0 {
+            return Some(HexFloatParts {
+                negative: self.negative,
+                significand: 0,
+                exponent: 0,
+            });
+        }
+        let shift = self.significand.trailing_zeros();
+        match self.exponent.checked_add(shift as isize) {
+            Some(exponent) => Some(HexFloatParts {
+                negative: self.negative,
+                significand: self.significand >> shift,
+                exponent,
+            }),
+            None => None,
+        }
+    }
+
+    /// Returns the number of significant bits, i.e. the number of bits from
+    /// the most significant non-zero bit to the least significant non-zero bit inclusive.
+    /// The value can be exactly represented in any binary format with at least this precision
+    /// if its exponent range suffices. This is zero for zeroes.
+    pub const fn significant_bits(&self) -> u32 {
+        if self.significand == 0 {
+            return 0;
+        }
+        128 - self.significand.leading_zeros() - self.significand.trailing_zeros()
+    }
+
+    /// Returns the binary exponent of the most significant non-zero bit,
+    /// i.e. `e` such that `2^e <= |value| < 2^(e+1)`, or `None` for zeroes.
+    /// This is the exponent in the usual `1.xxx * 2^e` notation.
+    /// Also returns `None` if the exponent doesn't fit in `isize`.
+    pub const fn msb_exponent(&self) -> Option<isize> {
+        if self.significand == 0 {
+            return None;
+        }
+        let msb = 127 - self.significand.leading_zeros() as isize;
+        self.exponent.checked_add(msb)
+    }
+
+    /// Returns the binary exponent of the least significant non-zero bit,
+    /// i.e. the exponent of the normalized representation, or `None` for zeroes.
+    /// Also returns `None` if the exponent doesn't fit in `isize`.
+    pub const fn lsb_exponent(&self) -> Option<isize> {
+        if self.significand == 0 {
+            return None;
+        }
+        let lsb = self.significand.trailing_zeros() as isize;
+        self.exponent.checked_add(lsb)
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to its exact parts
+/// without converting it to any floating point type.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This can read literals that don't fit in any supported type,
+/// as long as they have at most 125 significant bits, which always fit in the significand.
+/// Literals with more significant bits may still fit depending on their leading digit,
+/// and otherwise result in `ParseHexfErrorKind::Inexact`.
+/// Trailing zeroes after the decimal point are ignored, but other digits are kept as written,
+/// so the result is not necessarily normalized.
+/// Literals whose exponent doesn't fit in `isize` result in `ParseHexfErrorKind::Overflow`
+/// or `ParseHexfErrorKind::Underflow` instead.
+/// Infinities and NaNs result in `ParseHexfErrorKind::UnsupportedSpecial`,
+/// even when their payloads are not valid.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// let parts = parse_hexf_parts("0x1.8p100000", false).unwrap();
+/// assert_eq!(
+///     parts,
+///     HexFloatParts { negative: false, significand: 0x18, exponent: 100000 - 4 }
+/// );
+/// assert_eq!(parts.msb_exponent(), Some(100000));
+/// assert_eq!(parts.significant_bits(), 2);
+/// ```
+pub const fn parse_hexf_parts(
+    s: &str,
+    allow_underscore: bool,
+) -> Result<HexFloatParts, ParseHexfError> {
+    if is_special(s.as_bytes()) {
+        return Err(UNSUPPORTED_SPECIAL);
+    }
+    let (negative, significand, exponent, sticky) =
+        match parse_truncated(s.as_bytes(), allow_underscore) {
+            Ok(parsed) => parsed,
+            Err(err) => return Err(err),
+        };
+    if exponent > isize::MAX as i128 {
+        return Err(OVERFLOW);
+    }
+    if exponent < isize::MIN as i128 {
+        return Err(UNDERFLOW);
+    }
+    let exponent = exponent as isize;
+    if sticky {
+        let precision = 128 - significand.leading_zeros();
+        let err = ParseHexfError {
+            lost: Some((precision, exponent)),
+            ..INEXACT
+        };
+        return Err(err.locate(s.as_bytes()));
+    }
+    Ok(HexFloatParts {
+        negative,
+        significand,
+        exponent,
+    })
+}
+
+#[test]
+fn test_parse_hexf_parts() {
+    use crate::ParseHexfErrorKind::*;
+    let parts = |negative, significand, exponent| {
+        Ok(HexFloatParts {
+            negative,
+            significand,
+            exponent,
+        })
+    };
+    let kind = |s: &str| parse_hexf_parts(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf_parts("0x0p0", false), parts(false, 0, 0));
+    assert_eq!(parse_hexf_parts("-0x0.000p99", false), parts(true, 0, 0));
+    assert_eq!(parse_hexf_parts("0x1p0", false), parts(false, 1, 0));
+    assert_eq!(parse_hexf_parts("0x100p0", false), parts(false, 0x100, 0));
+    assert_eq!(
+        parse_hexf_parts("-0x3.14fp+3", false),
+        parts(true, 0x314f, 3 - 12)
+    );
+    assert_eq!(
+        parse_hexf_parts("0x1.800p-1", false),
+        parts(false, 0x18, -5)
+    );
+    assert_eq!(
+        parse_hexf_parts("0x1_0.0_1p0", true),
+        parts(false, 0x1001, -8)
+    );
+    assert_eq!(
+        parse_hexf_parts("0x1p-100000", false),
+        parts(false, 1, -100000)
+    );
+    // 125 significant bits
+    assert_eq!(
+        parse_hexf_parts("0x1.0000000000000000000000000000001p0", false),
+        parts(false, 1 << 124 | 1, -124)
+    );
+    assert_eq!(
+        parse_hexf_parts("0xffffffffffffffffffffffffffffffff0000p0", false),
+        parts(false, u128::MAX, 16)
+    );
+
+    assert_eq!(kind("0x1.00000000000000000000000000000001p0"), Inexact);
+    assert_eq!(kind("inf"), UnsupportedSpecial);
+    assert_eq!(kind("-nan:0x1"), UnsupportedSpecial);
+    assert_eq!(kind("nan:0x2"), UnsupportedSpecial);
+    assert_eq!(kind("snan:0x0"), UnsupportedSpecial);
+    assert_eq!(kind("Infinity:0x1"), UnsupportedSpecial);
+
+    // exponents out of `isize` shouldn't be saturated
+    let max = isize::MAX as i128;
+    let min = isize::MIN as i128;
+    assert_eq!(kind(&format!("0x1p{}", max + 1)), Overflow);
+    assert_eq!(kind(&format!("0x1.8p{}", max + 5)), Overflow);
+    assert_eq!(kind(&format!("0x1p{}", min - 1)), Underflow);
+    assert_eq!(kind(&format!("0x10p{}", min - 4)), Underflow);
+    assert_eq!(kind(&format!("0x1p{}0", max)), Overflow);
+    assert_eq!(kind(&format!("0x1p-{}0", max)), Underflow);
+    assert_eq!(
+        kind("0x1p99999999999999999999999999999999999999999999"),
+        Overflow
+    );
+    assert_eq!(
+        parse_hexf_parts(&format!("0x1p{}", max), false),
+        parts(false, 1, isize::MAX)
+    );
+    assert_eq!(
+        parse_hexf_parts(&format!("0x10p{}", min), false),
+        parts(false, 0x10, isize::MIN)
+    );
+    assert_eq!(kind("0x1.0"), MissingExponent);
+
+    let err = parse_hexf_parts("0x1.00000000000000000000000000000001p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(35));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 125 significant bits fit, bits from the digit at byte 35 are lost)"
+    );
+}
+
+#[test]
+fn test_hex_float_parts_helpers() {
+    let parts = |s: &str| parse_hexf_parts(s, true).unwrap();
+
+    let zero = parts("-0x0p0");
+    assert!(zero.is_zero());
+    assert_eq!(zero.normalized(), Some(zero));
+    assert_eq!(zero.significant_bits(), 0);
+    assert_eq!(zero.msb_exponent(), None);
+    assert_eq!(zero.lsb_exponent(), None);
+
+    let x = parts("0x1_0100p-16");
+    assert!(!x.is_zero());
+    assert_eq!(
+        x.normalized(),
+        Some(HexFloatParts {
+            negative: false,
+            significand: 0x101,
+            exponent: -8,
+        })
+    );
+    assert_eq!(x.normalized(), parts("0x1.01p0").normalized());
+    assert_eq!(x.significant_bits(), 9);
+    assert_eq!(x.msb_exponent(), Some(0));
+    assert_eq!(x.lsb_exponent(), Some(-8));
+    assert_eq!(x.normalized().unwrap().msb_exponent(), x.msb_exponent());
+
+    // exponents out of `isize` are not saturated
+    let x = parts(&format!("0x10p{}", isize::MAX));
+    assert_eq!(x.normalized(), None);
+    assert_eq!(x.msb_exponent(), None);
+    assert_eq!(x.lsb_exponent(), None);
+    let x = parts(&format!("0x1.8p{}", isize::MAX));
+    assert_eq!(x.msb_exponent(), Some(isize::MAX));
+    assert_eq!(x.lsb_exponent(), Some(isize::MAX - 1));
+}
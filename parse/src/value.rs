@@ -0,0 +1,877 @@
+//! Exact values of hexadecimal float literals with arbitrary precision.
+
+use crate::{
+    is_special, parse_truncated, round_ieee, HexFloatParts, ParseHexfError, RoundingMode, OVERFLOW,
+    UNDERFLOW, UNSUPPORTED_SPECIAL,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::{fmt, ops};
+
+/// An exact value of a hexadecimal float literal with an arbitrary precision,
+/// `(-1)^negative * significand * 2^exponent` where the significand is a big integer
+/// and the exponent is an `i64`.
+///
+/// This can be made from any finite literal by `parse_hexf_value` without any loss,
+/// and supports exact addition, subtraction, multiplication and scaling by powers of two.
+/// The result can be rounded to `f32` or `f64` at the end.
+///
+/// Comparisons and hashes are based on the value, so `0x1p0` and `0x10p-4` are equal.
+/// The sign of zeroes is kept for the conversion to floats,
+/// but a negative zero is still equal to a positive zero.
+///
+/// Arithmetic operations panic if the resulting exponent doesn't fit in `i64`.
+/// Note that the addition and subtraction need as much memory as the difference of exponents,
+/// so adding numbers with wildly different magnitudes can be very expensive,
+/// and panics if the difference doesn't fit in the address space.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// let a = parse_hexf_value("0x1p-2000", false).unwrap();
+/// let b = parse_hexf_value("0x1.8p+2000", false).unwrap();
+/// let c = &a * &b;
+/// assert_eq!(c.to_string(), "0x1.8p+0");
+/// assert_eq!(c.to_f64(), Ok(1.5));
+/// assert_eq!((&c + &a).to_f64_rounded(RoundingMode::TowardPositive), 1.5 + f64::EPSILON);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HexfValue {
+    negative: bool,
+    // the magnitude of the significand in little-endian 32-bit limbs,
+    // always odd (i.e. has no trailing zero bits) and has no most significant zero limbs.
+    // this is empty for zeroes, whose exponent is also always zero.
+    significand: Vec<u32>,
+    exponent: i64,
+}
+
+impl HexfValue {
+    // makes a canonical value out of the possibly non-canonical significand.
+    fn new(negative: bool, mut significand: Vec<u32>, exponent: i128) -> Result<Self, bool> {
+        while significand.last() == Some(&0) {
+            significand.pop();
+        }
+        if significand.is_empty() {
+            return Ok(HexfValue::zero(negative));
+        }
+        let zeroes = trailing_zeros(&significand);
+        let significand = shr(&significand, zeroes);
+        match i64::try_from(exponent + zeroes as i128) {
+            Ok(exponent) => Ok(HexfValue {
+                negative,
+                significand,
+                exponent,
+            }),
+            // returns true for overflow and false for underflow
+            Err(_) => Err(exponent > 0),
+        }
+    }
+
+    // same to `new` but panics on the exponent overflow.
+    fn new_or_panic(negative: bool, significand: Vec<u32>, exponent: i128) -> Self {
+        HexfValue::new(negative, significand, exponent).expect("exponent overflow")
+    }
+
+    fn zero(negative: bool) -> Self {
+        HexfValue {
+            negative,
+            significand: Vec::new(),
+            exponent: 0,
+        }
+    }
+
+    /// Returns the exact value of given `f64`, or `None` for infinities and NaNs.
+    pub fn from_f64(v: f64) -> Option<HexfValue> {
+        if !v.is_finite() {
+            return None;
+        }
+        let bits = v.to_bits();
+        let biased = (bits >> 52 & 0x7ff) as i128;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 1 << 52, biased - 1075)
+        };
+        Some(HexfValue::new_or_panic(
+            v.is_sign_negative(),
+            limbs(mantissa as u128),
+            exponent,
+        ))
+    }
+
+    /// Returns the exact value of given `f32`, or `None` for infinities and NaNs.
+    pub fn from_f32(v: f32) -> Option<HexfValue> {
+        // every finite `f32` is exactly representable as `f64`
+        HexfValue::from_f64(v as f64)
+    }
+
+    /// Returns true if the value is a zero of either sign.
+    pub fn is_zero(&self) -> bool {
+        self.significand.is_empty()
+    }
+
+    /// Returns true if the value has a negative sign, including the negative zero.
+    pub fn is_sign_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the number of significant bits, i.e. the number of bits from
+    /// the most significant non-zero bit to the least significant non-zero bit inclusive.
+    /// This is zero for zeroes.
+    pub fn significant_bits(&self) -> u64 {
+        bit_len(&self.significand)
+    }
+
+    /// Returns the value multiplied by `2^exp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting exponent doesn't fit in `i64`.
+    pub fn mul_pow2(&self, exp: i64) -> HexfValue {
+        if self.is_zero() {
+            return self.clone();
+        }
+        HexfValue {
+            negative: self.negative,
+            significand: self.significand.clone(),
+            exponent: self.exponent.checked_add(exp).expect("exponent overflow"),
+        }
+    }
+
+    // returns at most 128 most significant bits of the significand and their exponent,
+    // where the least significant bit is set if any bit has been truncated.
+    // the exponent is clamped to the range where it is guaranteed to overflow or underflow.
+    fn truncated(&self) -> (u128, isize) {
+        let len = bit_len(&self.significand);
+        let (mantissa, exponent) = if len > 128 {
+            // the significand is odd, so truncated bits are never all zeroes
+            let shift = len - 128;
+            let mut mantissa = 0u128;
+            for &limb in shr(&self.significand, shift).iter().rev() {
+                mantissa = mantissa << 32 | limb as u128;
+            }
+            (mantissa | 1, self.exponent as i128 + shift as i128)
+        } else {
+            let mut mantissa = 0u128;
+            for &limb in self.significand.iter().rev() {
+                mantissa = mantissa << 32 | limb as u128;
+            }
+            (mantissa, self.exponent as i128)
+        };
+        let exponent = exponent.clamp(-(1 << 30), 1 << 30) as isize;
+        (mantissa, exponent)
+    }
+
+    /// Tries to convert the value to `f64` exactly.
+    ///
+    /// The error is same to what `parse_hexf64` would return for the equivalent literal,
+    /// except that it doesn't have the offset of lost bits.
+    pub fn to_f64(&self) -> Result<f64, ParseHexfError> {
+        let (mantissa, exponent) = self.truncated();
+        match round_ieee(
+            self.negative,
+            mantissa,
+            exponent,
+            RoundingMode::NearestTiesEven,
+            11,
+            52,
+        ) {
+            (bits, None) => Ok(f64::from_bits(bits as u64)),
+            (_, Some(err)) => Err(err),
+        }
+    }
+
+    /// Converts the value to `f64`, rounding it with given `rounding` mode.
+    pub fn to_f64_rounded(&self, rounding: RoundingMode) -> f64 {
+        let (mantissa, exponent) = self.truncated();
+        let (bits, _) = round_ieee(self.negative, mantissa, exponent, rounding, 11, 52);
+        f64::from_bits(bits as u64)
+    }
+
+    /// Tries to convert the value to `f32` exactly.
+    ///
+    /// The error is same to what `parse_hexf32` would return for the equivalent literal,
+    /// except that it doesn't have the offset of lost bits.
+    pub fn to_f32(&self) -> Result<f32, ParseHexfError> {
+        let (mantissa, exponent) = self.truncated();
+        match round_ieee(
+            self.negative,
+            mantissa,
+            exponent,
+            RoundingMode::NearestTiesEven,
+            8,
+            23,
+        ) {
+            (bits, None) => Ok(f32::from_bits(bits as u32)),
+            (_, Some(err)) => Err(err),
+        }
+    }
+
+    /// Converts the value to `f32`, rounding it with given `rounding` mode.
+    pub fn to_f32_rounded(&self, rounding: RoundingMode) -> f32 {
+        let (mantissa, exponent) = self.truncated();
+        let (bits, _) = round_ieee(self.negative, mantissa, exponent, rounding, 8, 23);
+        f32::from_bits(bits as u32)
+    }
+
+    // compares magnitudes of two values.
+    fn cmp_abs(&self, other: &HexfValue) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+        // compare the exponents of most significant bits first,
+        // then the exponent difference is bounded by the bit length
+        let msb = |v: &HexfValue| v.exponent as i128 + bit_len(&v.significand) as i128;
+        msb(self).cmp(&msb(other)).then_with(|| {
+            let (a, b) = align(self, other);
+            cmp_mag(&a, &b)
+        })
+    }
+
+    fn add_signed(&self, other: &HexfValue, other_negative: bool) -> HexfValue {
+        match (self.is_zero(), other.is_zero()) {
+            // `-0 + -0` is the only sum of zeroes that is negative
+            (true, true) => return HexfValue::zero(self.negative && other_negative),
+            (true, false) => {
+                return HexfValue {
+                    negative: other_negative,
+                    ..other.clone()
+                }
+            }
+            (false, true) => return self.clone(),
+            (false, false) => {}
+        }
+        let exponent = self.exponent.min(other.exponent) as i128;
+        let (a, b) = align(self, other);
+        if self.negative == other_negative {
+            HexfValue::new_or_panic(self.negative, add_mag(&a, &b), exponent)
+        } else {
+            match cmp_mag(&a, &b) {
+                Ordering::Less => {
+                    HexfValue::new_or_panic(other_negative, sub_mag(&b, &a), exponent)
+                }
+                Ordering::Equal => HexfValue::zero(false),
+                Ordering::Greater => {
+                    HexfValue::new_or_panic(self.negative, sub_mag(&a, &b), exponent)
+                }
+            }
+        }
+    }
+}
+
+impl From<HexFloatParts> for HexfValue {
+    fn from(parts: HexFloatParts) -> HexfValue {
+        HexfValue::new_or_panic(
+            parts.negative,
+            limbs(parts.significand),
+            parts.exponent as i128,
+        )
+    }
+}
+
+impl PartialEq for HexfValue {
+    fn eq(&self, other: &HexfValue) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HexfValue {}
+
+impl PartialOrd for HexfValue {
+    fn partial_cmp(&self, other: &HexfValue) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HexfValue {
+    fn cmp(&self, other: &HexfValue) -> Ordering {
+        let self_negative = self.negative && !self.is_zero();
+        let other_negative = other.negative && !other.is_zero();
+        match (self_negative, other_negative) {
+            (false, false) => self.cmp_abs(other),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (true, true) => other.cmp_abs(self),
+        }
+    }
+}
+
+impl Hash for HexfValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // zeroes of both signs should have the same hash
+        (self.negative && !self.is_zero()).hash(state);
+        self.significand.hash(state);
+        self.exponent.hash(state);
+    }
+}
+
+/// Writes the exact value as a hexadecimal float literal with the minimal number of digits,
+/// e.g. `0x1.8p+1` or `-0x0p+0`, which can be read back with `parse_hexf_value`.
+impl fmt::Display for HexfValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        if self.is_zero() {
+            return write!(f, "{}0x0p+0", sign);
+        }
+        // align bits below the most significant bit to the hex digit boundary
+        let len = bit_len(&self.significand);
+        let ndigits = (len - 1).div_ceil(4);
+        let aligned = shl(&self.significand, ndigits * 4 - (len - 1));
+        write!(f, "{}0x1", sign)?;
+        if ndigits > 0 {
+            f.write_str(".")?;
+            for i in (0..ndigits).rev() {
+                let pos = i * 4;
+                let digit = aligned[(pos / 32) as usize] >> (pos % 32) & 0xf;
+                write!(f, "{:x}", digit)?;
+            }
+        }
+        write!(f, "p{:+}", self.exponent as i128 + (len - 1) as i128)
+    }
+}
+
+impl ops::Neg for HexfValue {
+    type Output = HexfValue;
+    fn neg(self) -> HexfValue {
+        HexfValue {
+            negative: !self.negative,
+            ..self
+        }
+    }
+}
+
+impl ops::Neg for &HexfValue {
+    type Output = HexfValue;
+    fn neg(self) -> HexfValue {
+        -self.clone()
+    }
+}
+
+impl ops::Add for &HexfValue {
+    type Output = HexfValue;
+    fn add(self, other: &HexfValue) -> HexfValue {
+        self.add_signed(other, other.negative)
+    }
+}
+
+impl ops::Sub for &HexfValue {
+    type Output = HexfValue;
+    fn sub(self, other: &HexfValue) -> HexfValue {
+        self.add_signed(other, !other.negative)
+    }
+}
+
+impl ops::Mul for &HexfValue {
+    type Output = HexfValue;
+    fn mul(self, other: &HexfValue) -> HexfValue {
+        let negative = self.negative != other.negative;
+        if self.is_zero() || other.is_zero() {
+            return HexfValue::zero(negative);
+        }
+        let exponent = self.exponent as i128 + other.exponent as i128;
+        HexfValue::new_or_panic(
+            negative,
+            mul_mag(&self.significand, &other.significand),
+            exponent,
+        )
+    }
+}
+
+// forwards operations on owned values, or mixed owned values and references, to references.
+macro_rules! forward_binop {
+    ($($imp:ident $method:ident;)*) => ($(
+        impl ops::$imp for HexfValue {
+            type Output = HexfValue;
+            fn $method(self, other: HexfValue) -> HexfValue {
+                ops::$imp::$method(&self, &other)
+            }
+        }
+
+        impl ops::$imp<&HexfValue> for HexfValue {
+            type Output = HexfValue;
+            fn $method(self, other: &HexfValue) -> HexfValue {
+                ops::$imp::$method(&self, other)
+            }
+        }
+
+        impl ops::$imp<HexfValue> for &HexfValue {
+            type Output = HexfValue;
+            fn $method(self, other: HexfValue) -> HexfValue {
+                ops::$imp::$method(self, &other)
+            }
+        }
+    )*)
+}
+
+forward_binop! {
+    Add add;
+    Sub sub;
+    Mul mul;
+}
+
+// converts a `u128` into limbs.
+fn limbs(mut v: u128) -> Vec<u32> {
+    let mut limbs = Vec::new();
+    while v != 0 {
+        limbs.push(v as u32);
+        v >>= 32;
+    }
+    limbs
+}
+
+// returns the number of bits in the magnitude without most significant zero limbs.
+fn bit_len(v: &[u32]) -> u64 {
+    match v.last() {
+        Some(&last) => v.len() as u64 * 32 - last.leading_zeros() as u64,
+        None => 0,
+    }
+}
+
+// returns the number of trailing zero bits in the non-zero magnitude.
+fn trailing_zeros(v: &[u32]) -> u64 {
+    let zeroes = v.iter().take_while(|&&limb| limb == 0).count();
+    zeroes as u64 * 32 + v[zeroes].trailing_zeros() as u64
+}
+
+// panics if the shift doesn't fit in the address space, where it couldn't be allocated anyway.
+fn shl(v: &[u32], n: u64) -> Vec<u32> {
+    let limbs = usize::try_from(n / 32).expect("shift too large");
+    let bits = (n % 32) as u32;
+    let mut r = vec![0; limbs];
+    if bits == 0 {
+        r.extend_from_slice(v);
+    } else {
+        let mut carry = 0;
+        for &limb in v {
+            r.push(limb << bits | carry);
+            carry = limb >> (32 - bits);
+        }
+        if carry != 0 {
+            r.push(carry);
+        }
+    }
+    r
+}
+
+fn shr(v: &[u32], n: u64) -> Vec<u32> {
+    // any shift beyond the address space discards every limb anyway
+    let limbs = usize::try_from(n / 32).unwrap_or(usize::MAX);
+    let bits = (n % 32) as u32;
+    let v = &v[limbs.min(v.len())..];
+    let mut r: Vec<u32> = if bits == 0 {
+        v.to_vec()
+    } else {
+        (0..v.len())
+            .map(|i| v[i] >> bits | v.get(i + 1).map_or(0, |&next| next << (32 - bits)))
+            .collect()
+    };
+    while r.last() == Some(&0) {
+        r.pop();
+    }
+    r
+}
+
+// shifts significands of two non-zero values to the common exponent.
+fn align(a: &HexfValue, b: &HexfValue) -> (Vec<u32>, Vec<u32>) {
+    let exponent = a.exponent.min(b.exponent) as i128;
+    let shift = |v: &HexfValue| shl(&v.significand, (v.exponent as i128 - exponent) as u64);
+    (shift(a), shift(b))
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut r = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u64;
+    for (i, &limb) in a.iter().enumerate() {
+        let sum = limb as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        r.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry != 0 {
+        r.push(carry as u32);
+    }
+    r
+}
+
+// subtracts `b` from `a`, where `a >= b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut r = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for (i, &limb) in a.iter().enumerate() {
+        let (diff, borrow1) = limb.overflowing_sub(b.get(i).copied().unwrap_or(0));
+        let (diff, borrow2) = diff.overflowing_sub(borrow as u32);
+        r.push(diff);
+        borrow = borrow1 || borrow2;
+    }
+    debug_assert!(!borrow);
+    r
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut r = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + r[i + j] as u64 + carry;
+            r[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        r[i + b.len()] = carry as u32;
+    }
+    r
+}
+
+/// Tries to parse a hexadecimal float literal to its exact value with an arbitrary precision.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// Unlike other parsing functions this never loses any bit of the literal,
+/// so it can be used for literals with an arbitrary number of digits.
+/// The exponent of the value should still fit in `i64`, and otherwise results in
+/// `ParseHexfErrorKind::Overflow` or `ParseHexfErrorKind::Underflow`.
+/// Infinities and NaNs result in `ParseHexfErrorKind::UnsupportedSpecial`,
+/// even when their payloads are not valid.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// let v = parse_hexf_value("0x1.0000000000000_0000000000000000_0000000000001p0", true).unwrap();
+/// assert_eq!(v.significant_bits(), 169);
+/// assert!(v > parse_hexf_value("0x1p0", false).unwrap());
+/// assert_eq!(v.to_f64_rounded(RoundingMode::TowardZero), 1.0);
+/// ```
+pub fn parse_hexf_value(s: &str, allow_underscore: bool) -> Result<HexfValue, ParseHexfError> {
+    let bytes = s.as_bytes();
+    if is_special(bytes) {
+        return Err(UNSUPPORTED_SPECIAL);
+    }
+    // this also validates the syntax, so the rest can be scanned without any check
+    let (negative, acc, _, _) = parse_truncated(bytes, allow_underscore)?;
+    if acc == 0 {
+        return Ok(HexfValue::zero(negative));
+    }
+
+    let s = match bytes {
+        [b'+', s @ ..] | [b'-', s @ ..] => s,
+        s => s,
+    };
+    let mut digits = Vec::new(); // in the reverse order
+    let mut nfracs = 0i128;
+    let mut in_fraction = false;
+    let mut rest = &s[2..];
+    while let Some((&c, rest_)) = rest.split_first() {
+        rest = rest_;
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            b'.' => {
+                in_fraction = true;
+                continue;
+            }
+            b'_' => continue,
+            _ => break, // `p` or `P`
+        };
+        digits.push(digit);
+        nfracs += in_fraction as i128;
+    }
+    digits.reverse();
+
+    let (rest, negative_exponent) = match rest {
+        [b'+', rest @ ..] => (rest, false),
+        [b'-', rest @ ..] => (rest, true),
+        rest => (rest, false),
+    };
+    let mut exponent = 0i128;
+    for &c in rest {
+        if c != b'_' {
+            // saturate way beyond the range of `i64`
+            exponent = (exponent * 10 + (c - b'0') as i128).min(1 << 100);
+        }
+    }
+    if negative_exponent {
+        exponent = -exponent;
+    }
+
+    let mut significand = vec![0u32; digits.len().div_ceil(8)];
+    for (i, &digit) in digits.iter().enumerate() {
+        significand[i / 8] |= (digit as u32) << (i % 8 * 4);
+    }
+    HexfValue::new(negative, significand, exponent - nfracs * 4).map_err(|overflow| {
+        if overflow {
+            OVERFLOW
+        } else {
+            UNDERFLOW
+        }
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_hexf_value() {
+    use crate::ParseHexfErrorKind::*;
+    let value = |s: &str| parse_hexf_value(s, true).unwrap();
+    let kind = |s| parse_hexf_value(s, true).unwrap_err().kind();
+    let string = |s| value(s).to_string();
+
+    assert_eq!(string("0x0p0"), "0x0p+0");
+    assert_eq!(string("-0x0.000p99999999999999999999999"), "-0x0p+0");
+    assert_eq!(string("0x1p0"), "0x1p+0");
+    assert_eq!(string("0x100p0"), "0x1p+8");
+    assert_eq!(string("-0x3.14fp+3"), "-0x1.8a78p+4");
+    assert_eq!(string("0x0.0_0_1p-4"), "0x1p-16");
+    assert_eq!(string("0x.8p1"), "0x1p+0");
+    assert_eq!(
+        string("0x1.8p9223372036854775807"),
+        "0x1.8p+9223372036854775807"
+    );
+    assert_eq!(
+        string("0x1p-9223372036854775808"),
+        "0x1p-9223372036854775808"
+    );
+    assert_eq!(
+        string("0x10p-9223372036854775812"),
+        "0x1p-9223372036854775808"
+    );
+    assert_eq!(
+        string("0x1.0000000000000_0000000000000000_0000000000001p0"),
+        "0x1.000000000000000000000000000000000000000001p+0"
+    );
+    assert_eq!(
+        string("0xffffffff_ffffffff_ffffffff_ffffffff_ffffffffp-160"),
+        "0x1.fffffffffffffffffffffffffffffffffffffffep-1"
+    );
+
+    assert_eq!(kind("0x1p9223372036854775808"), Overflow);
+    assert_eq!(kind("0x1p-9223372036854775809"), Underflow);
+    assert_eq!(
+        kind("0x1p99999999999999999999999999999999999999999999999"),
+        Overflow
+    );
+    assert_eq!(kind("inf"), UnsupportedSpecial);
+    assert_eq!(kind("-nan"), UnsupportedSpecial);
+    assert_eq!(kind("nan:0x2"), UnsupportedSpecial);
+    assert_eq!(kind("snan:0x0"), UnsupportedSpecial);
+    assert_eq!(kind("0x1.0"), MissingExponent);
+    assert_eq!(kind("0x_1p0"), MisplacedUnderscore);
+    assert_eq!(
+        parse_hexf_value("0x1_0p0", false).unwrap_err().kind(),
+        MisplacedUnderscore
+    );
+
+    // Display should read back to the same value
+    for s in [
+        "0x3.14fp+3",
+        "-0x1.0000000000000_0000000000000000_0000000000001p-99999",
+    ] {
+        assert_eq!(value(&value(s).to_string()), value(s));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hexf_value_eq_ord_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    let value = |s: &str| parse_hexf_value(s, true).unwrap();
+    let hash = |v: &HexfValue| {
+        let mut h = DefaultHasher::new();
+        v.hash(&mut h);
+        h.finish()
+    };
+
+    assert_eq!(value("0x1p0"), value("0x10p-4"));
+    assert_eq!(hash(&value("0x1p0")), hash(&value("0x0.1p4")));
+    assert_eq!(value("0x0p0"), value("-0x0p0"));
+    assert_eq!(hash(&value("0x0p0")), hash(&value("-0x0p5")));
+    assert_ne!(value("0x1p0"), value("-0x1p0"));
+
+    let sorted = [
+        "-0x1p100",
+        "-0x1.8p0",
+        "-0x1.0000000000000000000000000000000001p0",
+        "-0x1p0",
+        "-0x1p-100",
+        "-0x0p0",
+        "0x1p-100",
+        "0x1.fffp-1",
+        "0x1p0",
+        "0x1.0000000000000000000000000000000001p0",
+        "0x1.8p0",
+        "0x3p0",
+        "0x1p100",
+    ];
+    for (i, a) in sorted.iter().enumerate() {
+        for (j, b) in sorted.iter().enumerate() {
+            assert_eq!(value(a).cmp(&value(b)), i.cmp(&j), "{} <=> {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_hexf_value_arith() {
+    let value = |s: &str| parse_hexf_value(s, true).unwrap();
+
+    assert_eq!(
+        &value("0x1p0") + &value("0x1p-200"),
+        value("0x1.00000000000000000000000000000000000000000000000001p0")
+    );
+    assert_eq!(
+        &value("0x1p0") - &value("0x1p-200"),
+        value("0x0.ffffffffffffffffffffffffffffffffffffffffffffffffffp0")
+    );
+    assert_eq!(
+        &value("0x1p-200") - &value("0x1p0"),
+        value("-0x0.ffffffffffffffffffffffffffffffffffffffffffffffffffp0")
+    );
+    assert_eq!(value("0x1.8p1") + value("-0x1.8p1"), value("0x0p0"));
+    assert!(!(value("0x1.8p1") + value("-0x1.8p1")).is_sign_negative());
+    assert!((value("-0x0p0") + value("-0x0p0")).is_sign_negative());
+    assert!(!(value("-0x0p0") + value("0x0p0")).is_sign_negative());
+    assert_eq!(value("0x0p0") - value("0x1p5"), value("-0x1p5"));
+    assert_eq!(value("0x1p5") + value("0x0p0"), value("0x1p5"));
+
+    assert_eq!(value("0x1.8p0") * value("-0x1.8p0"), value("-0x2.4p0"));
+    assert!((value("-0x0p0") * value("0x1p0")).is_sign_negative());
+    let x = value("0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffffp0");
+    assert_eq!(
+        &x * &x,
+        value(
+            "0xffff_ffff_ffff_ffff_ffff_ffff_ffff_fffe_0000_0000_0000_0000_0000_0000_0000_0001p0"
+        )
+    );
+    assert_eq!(-&x, value("-0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffffp0"));
+
+    // mixed owned values and references
+    let (a, b) = (value("0x1.8p1"), value("-0x1p-1"));
+    assert_eq!(&a + b.clone(), value("0x2.8p0"));
+    assert_eq!(a.clone() + &b, value("0x2.8p0"));
+    assert_eq!(&a - b.clone(), value("0x3.8p0"));
+    assert_eq!(a.clone() - &b, value("0x3.8p0"));
+    assert_eq!(&a * b.clone(), value("-0x1.8p0"));
+    assert_eq!(a * &b, value("-0x1.8p0"));
+
+    assert_eq!(
+        value("0x1.8p0").mul_pow2(-1_000_000),
+        value("0x1.8p-1000000")
+    );
+    assert_eq!(value("-0x0p0").mul_pow2(100), value("0x0p0"));
+    assert_eq!(value("0x3p0").significant_bits(), 2);
+    assert_eq!(value("0x0p0").significant_bits(), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_hexf_value_exponent_overflow() {
+    let _ = parse_hexf_value("0x1p9223372036854775807", false)
+        .unwrap()
+        .mul_pow2(1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hexf_value_to_float() {
+    use crate::ParseHexfErrorKind::*;
+    use crate::RoundingMode::*;
+    let value = |s: &str| parse_hexf_value(s, true).unwrap();
+
+    assert_eq!(value("0x1.8p1").to_f64(), Ok(3.0));
+    assert_eq!(value("-0x0p0").to_f64().map(f64::to_bits), Ok(1 << 63));
+    assert_eq!(value("0x1p-1074").to_f64(), Ok(f64::from_bits(1)));
+    assert_eq!(value("0x1p-1075").to_f64().unwrap_err().kind(), Underflow);
+    assert_eq!(value("0x1p1024").to_f64().unwrap_err().kind(), Overflow);
+    assert_eq!(
+        value("0x1p9223372036854775807")
+            .to_f64()
+            .unwrap_err()
+            .kind(),
+        Overflow
+    );
+    assert_eq!(
+        value("0x1.00000000000008p0").to_f64().unwrap_err().kind(),
+        Inexact
+    );
+    assert_eq!(value("0x1.8p1").to_f32(), Ok(3.0));
+    assert_eq!(value("0x1.000001p0").to_f32().unwrap_err().kind(), Inexact);
+
+    let tie = value("0x1.00000000000008p0");
+    assert_eq!(tie.to_f64_rounded(NearestTiesEven), 1.0);
+    assert_eq!(tie.to_f64_rounded(NearestTiesAway), 1.0 + f64::EPSILON);
+    let above = &tie + &value("0x1p-1000");
+    assert_eq!(above.to_f64_rounded(NearestTiesEven), 1.0 + f64::EPSILON);
+    assert_eq!(above.to_f64_rounded(TowardZero), 1.0);
+    assert_eq!(
+        (-&above).to_f64_rounded(TowardNegative),
+        -1.0 - f64::EPSILON
+    );
+    assert_eq!(
+        value("0x1p-9223372036854775808").to_f64_rounded(TowardPositive),
+        f64::from_bits(1)
+    );
+    assert_eq!(value("-0x1p9999").to_f32_rounded(TowardZero), f32::MIN);
+    assert_eq!(
+        value("0x1p9999").to_f32_rounded(NearestTiesEven),
+        f32::INFINITY
+    );
+
+    // should agree with parsing functions, which truncate the literal with the sticky bit
+    let mut rng = crate::XorShift64(0x2545_f491_4f6c_dd1du64);
+    for _ in 0..1000 {
+        let mut s = String::from("0x1.");
+        let mut state = 0;
+        for _ in 0..40 {
+            state = rng.next().unwrap();
+            s.push(b"0123456789abcdef"[(state >> 60) as usize] as char);
+        }
+        s.push_str(&format!("p{}", (state >> 32) as i16 / 16));
+        let v = value(&s);
+        for &rounding in &[NearestTiesEven, TowardZero, TowardPositive, ToOdd] {
+            assert_eq!(
+                v.to_f64_rounded(rounding),
+                crate::parse_hexf64_rounded(&s, false, rounding).unwrap()
+            );
+            assert_eq!(
+                v.to_f32_rounded(rounding),
+                crate::parse_hexf32_rounded(&s, false, rounding).unwrap()
+            );
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hexf_value_from() {
+    assert_eq!(
+        HexfValue::from_f64(0.1).unwrap().to_string(),
+        "0x1.999999999999ap-4"
+    );
+    assert_eq!(
+        HexfValue::from_f64(f64::from_bits(1)).unwrap().to_string(),
+        "0x1p-1074"
+    );
+    assert_eq!(
+        HexfValue::from_f64(-0.0)
+            .unwrap()
+            .to_f64()
+            .map(f64::to_bits),
+        Ok(1 << 63)
+    );
+    assert_eq!(
+        HexfValue::from_f32(0.1).unwrap().to_string(),
+        "0x1.99999ap-4"
+    );
+    assert_eq!(HexfValue::from_f64(f64::NAN), None);
+    assert_eq!(HexfValue::from_f32(f32::INFINITY), None);
+
+    let parts = crate::parse_hexf_parts("-0x1_0p-4", true).unwrap();
+    assert_eq!(HexfValue::from(parts).to_string(), "-0x1p+0");
+}
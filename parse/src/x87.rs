@@ -0,0 +1,185 @@
+//! Parses hexadecimal float literals into the x87 80-bit extended precision format.
+
+use crate::{parse, parse_special, round_ieee, special_ieee, ParseHexfError, RoundingMode};
+
+// converts the bits of an IEEE-like format with 15 exponent bits and 63 fraction bits
+// into the x87 extended precision format, which has an explicit integer bit instead.
+// the integer bit is set for every value with a non-zero biased exponent, including
+// infinities and NaNs, so this always results in the canonical encoding.
+const fn to_x87_bytes(bits: u128) -> [u8; 10] {
+    let sign_exponent = (bits >> 63) as u16;
+    let integer = (sign_exponent & 0x7fff != 0) as u64;
+    let significand = integer << 63 | (bits as u64 & !(1 << 63));
+    let x87 = (sign_exponent as u128) << 64 | significand as u128;
+    let bytes = x87.to_le_bytes();
+    [
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7], bytes[8],
+        bytes[9],
+    ]
+}
+
+/// Tries to parse a hexadecimal float literal to the x87 80-bit extended precision format.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// The result is 10 bytes in the little endian order as stored in the memory
+/// (i.e. the 64-bit significand followed by the sign and the 15-bit biased exponent).
+/// The significand has an explicit integer bit, which is always set for normal numbers,
+/// infinities and NaNs and cleared for subnormal numbers and zeroes.
+/// Therefore pseudo-denormals and other non-canonical encodings are never produced.
+///
+/// As with `parse_hexf32`, the literal should be exactly representable (in 64 significant bits),
+/// and infinities and NaNs (optionally with a payload below the quiet bit 62) are also accepted.
+///
+/// ```rust
+/// use hexf_parse::*;
+/// assert_eq!(
+///     parse_hexf80("0x1p0", false),
+///     Ok([0, 0, 0, 0, 0, 0, 0, 0x80, 0xff, 0x3f])
+/// );
+/// assert_eq!(
+///     parse_hexf80("0xc.ccccccccccccccdp-7", false),
+///     Ok([0xcd, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xfb, 0x3f])
+/// );
+/// assert!(parse_hexf80("0x1.00000000000000008p0", false).is_err());
+/// ```
+pub fn parse_hexf80(s: &str, allow_underscore: bool) -> Result<[u8; 10], ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 63)? {
+        return Ok(to_x87_bytes(special_ieee(negative, special, 15, 63)));
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    match round_ieee(
+        negative,
+        mantissa,
+        exponent,
+        RoundingMode::TowardZero,
+        15,
+        63,
+    ) {
+        (bits, None) => Ok(to_x87_bytes(bits)),
+        (_, Some(err)) => Err(err.locate(s.as_bytes())),
+    }
+}
+
+/// Tries to parse a hexadecimal float literal to the x87 80-bit extended precision format,
+/// rounding it with given `rounding` mode.
+/// The underscore is allowed only when `allow_underscore` is true.
+///
+/// This never fails for inexact, overflowing or underflowing values.
+/// Infinities and NaNs are accepted as in `parse_hexf80` and never rounded.
+pub fn parse_hexf80_rounded(
+    s: &str,
+    allow_underscore: bool,
+    rounding: RoundingMode,
+) -> Result<[u8; 10], ParseHexfError> {
+    if let Some((negative, special)) = parse_special(s.as_bytes(), allow_underscore, 63)? {
+        return Ok(to_x87_bytes(special_ieee(negative, special, 15, 63)));
+    }
+    let (negative, mantissa, exponent) = parse(s.as_bytes(), allow_underscore)?;
+    Ok(to_x87_bytes(
+        round_ieee(negative, mantissa, exponent, rounding, 15, 63).0,
+    ))
+}
+
+// makes the bytes of an x87 extended precision number for tests.
+#[cfg(test)]
+pub(crate) fn x87(sign_exponent: u16, significand: u64) -> [u8; 10] {
+    let x87 = (sign_exponent as u128) << 64 | significand as u128;
+    let mut bytes = [0; 10];
+    bytes.copy_from_slice(&x87.to_le_bytes()[..10]);
+    bytes
+}
+
+#[test]
+fn test_parse_hexf80() {
+    use crate::ParseHexfErrorKind::*;
+    let kind = |s| parse_hexf80(s, true).unwrap_err().kind();
+
+    assert_eq!(parse_hexf80("0x0p0", false), Ok(x87(0, 0)));
+    assert_eq!(parse_hexf80("-0x0p0", false), Ok(x87(0x8000, 0)));
+    assert_eq!(
+        parse_hexf80("0x1p0", false),
+        Ok(x87(0x3fff, 0x8000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80("-0x1.8p1", false),
+        Ok(x87(0xc000, 0xc000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80("0x1.fffffffffffffffep16383", false),
+        Ok(x87(0x7ffe, 0xffff_ffff_ffff_ffff))
+    );
+    assert_eq!(
+        parse_hexf80("0x1p-16382", false),
+        Ok(x87(0x0001, 0x8000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80("0x0.fffffffffffffffep-16382", false),
+        Ok(x87(0x0000, 0x7fff_ffff_ffff_ffff))
+    );
+    assert_eq!(parse_hexf80("0x1p-16445", false), Ok(x87(0x0000, 1)));
+    assert_eq!(
+        parse_hexf80("0xffff_ffff_ffff_ffffp0", true),
+        Ok(x87(0x403e, 0xffff_ffff_ffff_ffff))
+    );
+    assert_eq!(
+        parse_hexf80("inf", false),
+        Ok(x87(0x7fff, 0x8000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80("-inf", false),
+        Ok(x87(0xffff, 0x8000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80("nan", false),
+        Ok(x87(0x7fff, 0xc000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80("-snan:0x1", false),
+        Ok(x87(0xffff, 0x8000_0000_0000_0001))
+    );
+    assert_eq!(
+        parse_hexf80("nan:0x3fff_ffff_ffff_ffff", true),
+        Ok(x87(0x7fff, 0xffff_ffff_ffff_ffff))
+    );
+
+    assert_eq!(kind("0x1p16384"), Overflow);
+    assert_eq!(kind("0x1p-16446"), Underflow);
+    assert_eq!(kind("0x1.00000000000000008p0"), Inexact);
+    assert_eq!(kind("0x1_ffff_ffff_ffff_ffffp0"), Inexact);
+    assert_eq!(kind("snan:0x4000_0000_0000_0000"), NanPayloadOverflow);
+
+    let err = parse_hexf80("0x1.00000000000000008p0", false).unwrap_err();
+    assert_eq!(err.offset(), Some(20));
+    assert_eq!(
+        err.to_string(),
+        "cannot exactly represent float in target type \
+         (only 64 significant bits fit, bits from the digit at byte 20 are lost)"
+    );
+}
+
+#[test]
+fn test_parse_hexf80_rounded() {
+    use crate::RoundingMode::*;
+
+    assert_eq!(
+        parse_hexf80_rounded("0x1.999999999999999999p-4", false, NearestTiesEven),
+        Ok(x87(0x3ffb, 0xcccc_cccc_cccc_cccd))
+    );
+    assert_eq!(
+        parse_hexf80_rounded("0x1.999999999999999999p-4", false, TowardZero),
+        Ok(x87(0x3ffb, 0xcccc_cccc_cccc_cccc))
+    );
+    assert_eq!(
+        parse_hexf80_rounded("0x1.ffffffffffffffffp16383", false, NearestTiesEven),
+        Ok(x87(0x7fff, 0x8000_0000_0000_0000))
+    );
+    assert_eq!(
+        parse_hexf80_rounded("-0x1p16384", false, TowardZero),
+        Ok(x87(0xfffe, 0xffff_ffff_ffff_ffff))
+    );
+    // rounding up from the largest subnormal number results in the smallest normal number
+    assert_eq!(
+        parse_hexf80_rounded("0x0.ffffffffffffffffp-16382", false, NearestTiesEven),
+        Ok(x87(0x0001, 0x8000_0000_0000_0000))
+    );
+}
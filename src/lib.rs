@@ -1,18 +1,300 @@
-//! Hexadecimal float support for Rust 1.43 or later.
+//! Hexadecimal float support for Rust 1.83 or later.
 //!
 //! ```rust
-//! use hexf::{hexf32, hexf64};
+//! use hexf::{hexf16, hexf32, hexf64};
 //!
 //! # fn main() {
 //! assert_eq!(hexf32!("0x1.99999ap-4"), 0.1f32);
 //! assert_eq!(hexf64!("0x1.999999999999ap-4"), 0.1f64);
+//! assert_eq!(hexf16!("0x1.998p-4"), 0x2e66u16); // the bits of binary16
 //! # }
 //! ```
 
+use hexf_parse::{ParseHexfError, ParseHexfErrorKind, RoundingMode};
 use proc_macro::TokenStream;
 
+// returns the span of the character at given byte offset in the literal value if possible.
+// this requires `Literal::subspan`, which is only available in nightly compilers.
+fn subspan(lit: &syn::LitStr, offset: usize) -> Option<proc_macro2::Span> {
+    let token = lit.token();
+    let source = token.to_string();
+    let value = lit.value();
+
+    // only the literal without any escape sequences can be mapped back
+    let start = source.find('"')? + 1;
+    if source.get(start..start + value.len())? != value {
+        return None;
+    }
+    token.subspan(start + offset..start + offset + 1)
+}
+
+// explains why the literal is not representable and suggests its nearest representable values.
+// `neighbour` should return the formatted literal rounded to given direction.
+fn suggest<F>(err: &ParseHexfError, neighbour: F) -> Option<String>
+where
+    F: Fn(RoundingMode) -> Option<String>,
+{
+    let reason = match err.kind() {
+        ParseHexfErrorKind::Overflow => "the literal overflowed",
+        ParseHexfErrorKind::Underflow => "the literal underflowed",
+        ParseHexfErrorKind::Inexact => "the literal had too many significant bits",
+        _ => return None,
+    };
+    let down = neighbour(RoundingMode::TowardNegative)?;
+    let up = neighbour(RoundingMode::TowardPositive)?;
+    if down == up {
+        // formats without infinities saturate to the largest finite value in both directions
+        return Some(format!(
+            "{}; the nearest representable value is `{}`",
+            reason, down
+        ));
+    }
+    Some(format!(
+        "{}; the nearest representable values are `{}` (rounded down) and `{}` (rounded up)",
+        reason, down, up
+    ))
+}
+
+// reports given parsing error, pointing at the offending character if possible.
+fn error(name: &str, lit: &syn::LitStr, err: ParseHexfError, note: Option<String>) -> TokenStream {
+    let span = err
+        .offset()
+        .and_then(|offset| subspan(lit, offset))
+        .unwrap_or_else(|| lit.span());
+    let mut message = format!("{}! failed: {}", name, err);
+    if let Some(note) = note {
+        message.push_str("\nnote: ");
+        message.push_str(&note);
+    }
+    syn::Error::new(span, message).to_compile_error().into()
+}
+
+// expands to a constant expression constructing the float type `ty` from given bits,
+// which should fit in the unsigned integer type `bits_ty`.
+//
+// this is bit-exact by construction, so the sign of zeroes and NaN payloads are kept as well.
+fn from_bits(ty: &str, bits: u64, bits_ty: &str) -> TokenStream {
+    format!(
+        "::core::primitive::{}::from_bits({:#x}{})",
+        ty, bits, bits_ty
+    )
+    .parse()
+    .expect("formatted a bit pattern")
+}
+
+// expands to a tuple of two given expressions.
+fn pair(first: TokenStream, second: TokenStream) -> TokenStream {
+    format!("({}, {})", first, second)
+        .parse()
+        .expect("formatted a tuple")
+}
+
+#[test]
+fn test_suggest() {
+    let suggest32 = |s: &str| {
+        let err = hexf_parse::parse_hexf32(s, true).unwrap_err();
+        suggest(&err, |rounding| {
+            let v = hexf_parse::parse_hexf32_rounded(s, true, rounding).ok()?;
+            Some(hexf_parse::format_hexf32(v))
+        })
+    };
+    assert_eq!(
+        suggest32("0x1.99999bp-4").as_deref(),
+        Some(
+            "the literal had too many significant bits; the nearest representable values are \
+             `0x1.99999ap-4` (rounded down) and `0x1.99999cp-4` (rounded up)"
+        )
+    );
+    assert_eq!(
+        suggest32("-0x1.0p128").as_deref(),
+        Some(
+            "the literal overflowed; the nearest representable values are \
+             `-inf` (rounded down) and `-0x1.fffffep+127` (rounded up)"
+        )
+    );
+    assert_eq!(
+        suggest32("0x1.0p-150").as_deref(),
+        Some(
+            "the literal underflowed; the nearest representable values are \
+             `0x0.000000p+0` (rounded down) and `0x0.000002p-126` (rounded up)"
+        )
+    );
+    assert_eq!(suggest32("0x1.0q0"), None);
+
+    let err = hexf_parse::parse_hexf8e4m3("0x1.ep8", true).unwrap_err();
+    let note = suggest(&err, |rounding| {
+        let bits = hexf_parse::parse_hexf8e4m3_rounded("0x1.ep8", true, rounding).ok()?;
+        Some(hexf_parse::format_hexf8e4m3(bits))
+    });
+    assert_eq!(
+        note.as_deref(),
+        Some("the literal overflowed; the nearest representable value is `0x1.cp+8`")
+    );
+}
+
+/// Expands to the bits of IEEE 754 binary16 (`f16`) value with given hexadecimal representation.
+///
+/// The result is a `u16` constant, which can be converted to the actual half precision type
+/// with its `from_bits` (e.g. `half::f16::from_bits`, or the primitive `f16::from_bits`).
+/// The literal should be exactly representable as in `hexf32!`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexf16; fn main() {
+/// assert_eq!(hexf16!("0x1.554p-2"), 0x3555u16);
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexf16(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexf16(&lit.value(), true) {
+        Ok(bits) => format!("{:#x}u16", bits)
+            .parse()
+            .expect("formatted a u16 literal"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let bits = hexf_parse::parse_hexf16_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexf16(bits))
+            });
+            error("hexf16", &lit, e, note)
+        }
+    }
+}
+
+/// Expands to the bits of IEEE 754 binary128 (`f128`) value with given hexadecimal representation.
+///
+/// The result is a `u128` constant, which can be converted to the actual quadruple precision type
+/// with its `from_bits` (e.g. the primitive `f128::from_bits`).
+/// The literal should be exactly representable as in `hexf32!`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexf128; fn main() {
+/// assert_eq!(
+///     hexf128!("0x1.999999999999999999999999999ap-4"),
+///     0x3ffb_9999_9999_9999_9999_9999_9999_999au128
+/// );
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexf128(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexf128(&lit.value(), true) {
+        Ok(bits) => format!("{:#x}u128", bits)
+            .parse()
+            .expect("formatted a u128 literal"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let bits = hexf_parse::parse_hexf128_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexf128(bits))
+            });
+            error("hexf128", &lit, e, note)
+        }
+    }
+}
+
+/// Expands to the bits of bfloat16 value with given hexadecimal representation.
+///
+/// The result is a `u16` constant, which can be converted to the actual bfloat16 type
+/// with its `from_bits` (e.g. `half::bf16::from_bits`).
+/// The literal should be exactly representable as in `hexf32!`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexbf16; fn main() {
+/// assert_eq!(hexbf16!("0x1.98p-4"), 0x3dccu16);
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexbf16(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexbf16(&lit.value(), true) {
+        Ok(bits) => format!("{:#x}u16", bits)
+            .parse()
+            .expect("formatted a u16 literal"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let bits = hexf_parse::parse_hexbf16_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexbf16(bits))
+            });
+            error("hexbf16", &lit, e, note)
+        }
+    }
+}
+
+/// Expands to the bits of OCP FP8 E4M3 value with given hexadecimal representation.
+///
+/// The result is a `u8` constant. E4M3 has no infinities and a single NaN per sign,
+/// so the largest finite value is `0x1.cp8` and `inf` or NaN payloads are rejected;
+/// see `hexf_parse::parse_hexf8e4m3` for details.
+/// The literal should be exactly representable as in `hexf32!`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexf8e4m3; fn main() {
+/// assert_eq!(hexf8e4m3!("0x1.cp8"), 0x7eu8);
+/// assert_eq!(hexf8e4m3!("-nan"), 0xffu8);
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexf8e4m3(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexf8e4m3(&lit.value(), true) {
+        Ok(bits) => format!("{:#x}u8", bits)
+            .parse()
+            .expect("formatted a u8 literal"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let bits =
+                    hexf_parse::parse_hexf8e4m3_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexf8e4m3(bits))
+            });
+            error("hexf8e4m3", &lit, e, note)
+        }
+    }
+}
+
+/// Expands to the bits of OCP FP8 E5M2 value with given hexadecimal representation.
+///
+/// The result is a `u8` constant. E5M2 follows IEEE 754 conventions,
+/// so infinities and NaNs are accepted as in `hexf32!`.
+/// The literal should be exactly representable as in `hexf32!`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexf8e5m2; fn main() {
+/// assert_eq!(hexf8e5m2!("0x1.cp15"), 0x7bu8);
+/// assert_eq!(hexf8e5m2!("-inf"), 0xfcu8);
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexf8e5m2(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexf8e5m2(&lit.value(), true) {
+        Ok(bits) => format!("{:#x}u8", bits)
+            .parse()
+            .expect("formatted a u8 literal"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let bits =
+                    hexf_parse::parse_hexf8e5m2_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexf8e5m2(bits))
+            });
+            error("hexf8e5m2", &lit, e, note)
+        }
+    }
+}
+
 /// Expands to a `f32` value with given hexadecimal representation.
 ///
+/// The value is constructed from its exact bits with `f32::from_bits`,
+/// so it can be also used in constant expressions.
+///
 /// # Example
 ///
 /// ```rust
@@ -24,17 +306,22 @@ use proc_macro::TokenStream;
 pub fn hexf32(input: TokenStream) -> TokenStream {
     let lit = syn::parse_macro_input!(input as syn::LitStr);
     match hexf_parse::parse_hexf32(&lit.value(), true) {
-        Ok(v) => format!("{:?}f32", v) // should keep the sign even for -0.0
-            .parse()
-            .expect("formatted a f32 literal"),
-        Err(e) => syn::Error::new(lit.span(), format!("hexf32! failed: {}", e))
-            .to_compile_error()
-            .into(),
+        Ok(v) => from_bits("f32", v.to_bits().into(), "u32"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let v = hexf_parse::parse_hexf32_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexf32(v))
+            });
+            error("hexf32", &lit, e, note)
+        }
     }
 }
 
 /// Expands to a `f64` value with given hexadecimal representation.
 ///
+/// The value is constructed from its exact bits with `f64::from_bits`,
+/// so it can be also used in constant expressions.
+///
 /// # Example
 ///
 /// ```rust
@@ -46,11 +333,65 @@ pub fn hexf32(input: TokenStream) -> TokenStream {
 pub fn hexf64(input: TokenStream) -> TokenStream {
     let lit = syn::parse_macro_input!(input as syn::LitStr);
     match hexf_parse::parse_hexf64(&lit.value(), true) {
-        Ok(v) => format!("{:?}f64", v) // should keep the sign even for -0.0
-            .parse()
-            .expect("formatted a f64 literal"),
-        Err(e) => syn::Error::new(lit.span(), format!("hexf64! failed: {}", e))
-            .to_compile_error()
-            .into(),
+        Ok(v) => from_bits("f64", v.to_bits(), "u64"),
+        Err(e) => {
+            let note = suggest(&e, |rounding| {
+                let v = hexf_parse::parse_hexf64_rounded(&lit.value(), true, rounding).ok()?;
+                Some(hexf_parse::format_hexf64(v))
+            });
+            error("hexf64", &lit, e, note)
+        }
+    }
+}
+
+/// Expands to a double-double, i.e. a `(f64, f64)` tuple for an unevaluated sum `hi + lo`,
+/// with given hexadecimal representation.
+///
+/// `hi` is the nearest `f64` to the literal and `lo` is the exact remainder,
+/// so they don't overlap. The literal should be exactly representable in this way;
+/// see `hexf_parse::parse_hexf64x2` for details.
+/// Both values are constructed from their exact bits, so it can be also used in constant expressions.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexf64x2; fn main() {
+/// const PI: (f64, f64) = hexf64x2!("0x3.243f6a8885a308d313198a2e038p0");
+/// assert_eq!(PI, (std::f64::consts::PI, 1.2246467991473532e-16));
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexf64x2(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexf64x2(&lit.value(), true) {
+        Ok((hi, lo)) => pair(
+            from_bits("f64", hi.to_bits(), "u64"),
+            from_bits("f64", lo.to_bits(), "u64"),
+        ),
+        Err(e) => error("hexf64x2", &lit, e, None),
+    }
+}
+
+/// Expands to a `(f32, f32)` tuple for an unevaluated sum `hi + lo`
+/// with given hexadecimal representation.
+///
+/// This is same to `hexf64x2!` but for `f32`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hexf::hexf32x2; fn main() {
+/// assert_eq!(hexf32x2!("0x1.000001p0"), (1.0f32, 5.9604645e-8f32));
+/// # }
+/// ```
+#[proc_macro]
+pub fn hexf32x2(input: TokenStream) -> TokenStream {
+    let lit = syn::parse_macro_input!(input as syn::LitStr);
+    match hexf_parse::parse_hexf32x2(&lit.value(), true) {
+        Ok((hi, lo)) => pair(
+            from_bits("f32", hi.to_bits().into(), "u32"),
+            from_bits("f32", lo.to_bits().into(), "u32"),
+        ),
+        Err(e) => error("hexf32x2", &lit, e, None),
     }
 }
@@ -1,6 +1,6 @@
 #![allow(clippy::float_cmp)]
 
-use hexf::{hexf32, hexf64};
+use hexf::{hexbf16, hexf128, hexf16, hexf32, hexf32x2, hexf64, hexf64x2, hexf8e4m3, hexf8e5m2};
 
 use std::f64;
 
@@ -32,3 +32,112 @@ fn syntax() {
     assert_eq!(hexf32!(r"0x1.0p0"), 1.0f32);
     assert_eq!(hexf64!(r"0x1.0p0"), 1.0f64);
 }
+
+#[test]
+fn long_significands() {
+    assert_eq!(hexf32!("0x10000000000000000000p-76"), 1.0f32);
+    assert_eq!(hexf64!("0x1.00000000000000000000000000p0"), 1.0f64);
+    assert_eq!(
+        hexf64!("0x0.000000000000000000001999999999999ap0"),
+        0.1f64 / 2f64.powi(80)
+    );
+}
+
+#[test]
+fn special_values() {
+    assert_eq!(hexf32!("inf"), f32::INFINITY);
+    assert_eq!(hexf32!("-Infinity"), f32::NEG_INFINITY);
+    assert_eq!(hexf64!("+INF"), f64::INFINITY);
+    assert_eq!(hexf64!("-infinity"), f64::NEG_INFINITY);
+    assert!(hexf32!("NaN").is_nan());
+    assert!(hexf64!("nan").is_nan());
+    assert!(hexf64!("nan").is_sign_positive());
+    assert!(hexf64!("-nan").is_sign_negative());
+}
+
+#[test]
+fn nan_payloads() {
+    assert_eq!(hexf32!("nan:0x1").to_bits(), 0x7fc0_0001);
+    assert_eq!(hexf32!("-snan:0x1").to_bits(), 0xff80_0001);
+    assert_eq!(
+        hexf64!("nan:0x8000000000001").to_bits(),
+        0x7ff8_0000_0000_0001
+    );
+    assert_eq!(hexf64!("-snan:0x1").to_bits(), 0xfff0_0000_0000_0001);
+    assert_eq!(
+        hexf64!("nan:0x7_ffff_ffff_ffff").to_bits(),
+        0x7fff_ffff_ffff_ffff
+    );
+}
+
+const TENTH: f64 = hexf64!("0x1.999999999999ap-4");
+const NEG_ZERO: f32 = hexf32!("-0x0p0");
+static NAN_BOX: f64 = hexf64!("-nan:0x1234");
+
+#[test]
+fn constants() {
+    assert_eq!(TENTH, 0.1f64);
+    assert_eq!(NEG_ZERO.to_bits(), 0x8000_0000);
+    assert_eq!(NAN_BOX.to_bits(), 0xfff8_0000_0000_1234);
+    assert_eq!(hexf32!("0x1.fffffep+127"), f32::MAX);
+    assert_eq!(hexf64!("0x0.0000000000001p-1022").to_bits(), 1);
+}
+
+const HALF_MAX: u16 = hexf16!("0x1.ffcp15");
+
+#[test]
+fn binary16() {
+    assert_eq!(HALF_MAX, 0x7bff);
+    assert_eq!(hexf16!("0x1p0"), 0x3c00);
+    assert_eq!(hexf16!("-0x0p0"), 0x8000);
+    assert_eq!(hexf16!("0x1p-24"), 0x0001);
+    assert_eq!(hexf16!("-inf"), 0xfc00);
+    assert_eq!(hexf16!("nan:0x1"), 0x7e01);
+}
+
+#[test]
+fn bfloat16() {
+    assert_eq!(hexbf16!("0x1p0"), 0x3f80);
+    assert_eq!(hexbf16!("-0x1.8p1"), 0xc040);
+    assert_eq!(hexbf16!("0x1.fep127"), 0x7f7f);
+    assert_eq!(hexbf16!("0x1p-133"), 0x0001);
+    assert_eq!(hexbf16!("inf"), 0x7f80);
+    assert_eq!(hexbf16!("0x1.98p-4"), (0.1f32.to_bits() >> 16) as u16);
+}
+
+#[test]
+fn binary128() {
+    assert_eq!(hexf128!("0x1p0"), 0x3fff << 112);
+    assert_eq!(hexf128!("-0x0p0"), 1 << 127);
+    assert_eq!(
+        hexf128!("0x1.ffff_ffff_ffff_ffff_ffff_ffff_ffffp16383"),
+        0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff
+    );
+    assert_eq!(hexf128!("0x1p-16494"), 1);
+    assert_eq!(hexf128!("-inf"), 0xffff << 112);
+}
+
+#[test]
+fn double_double() {
+    const LN2: (f64, f64) = hexf64x2!("0x1.62e42fefa39ef_35793c7673007ep-1");
+    assert_eq!(LN2.0, f64::consts::LN_2);
+    assert_eq!(LN2.0 + LN2.1, LN2.0);
+    assert_eq!(
+        hexf64x2!("-0x1.0000000000001_8p0"),
+        (-1.0000000000000004, 1.1102230246251565e-16)
+    );
+    assert_eq!(hexf64x2!("-0x0p0").1.to_bits(), 1 << 63);
+    assert_eq!(hexf32x2!("0x1.000003p0"), (1.0000002, -5.9604645e-8));
+}
+
+#[test]
+fn fp8() {
+    assert_eq!(hexf8e4m3!("0x1p0"), 0x38);
+    assert_eq!(hexf8e4m3!("-0x1.cp8"), 0xfe);
+    assert_eq!(hexf8e4m3!("0x1p-9"), 0x01);
+    assert_eq!(hexf8e4m3!("nan"), 0x7f);
+    assert_eq!(hexf8e5m2!("0x1p0"), 0x3c);
+    assert_eq!(hexf8e5m2!("-0x1.cp15"), 0xfb);
+    assert_eq!(hexf8e5m2!("inf"), 0x7c);
+    assert_eq!(hexf8e5m2!("nan"), 0x7e);
+}